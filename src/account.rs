@@ -1,16 +1,24 @@
+use std::time::SystemTime;
+
 use reqwest::StatusCode;
-use serde::Deserialize;
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::{client::Client, EasyBit, Error};
+use crate::{
+    amount::{check_extra_fee, parse_amount, parse_optional_amount},
+    client::Client,
+    response::{api_error, handle_data_response, payload},
+    Error,
+};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[allow(non_snake_case)]
 /**
     ### Account information.
     
     - `level`: Account level
-    - `volume`: Your volume in USDT in the last month
+    - `volume`: Your volume in USDT in the last month, parsed by `volume_usdt`
     - `fee`: EasyBit's fee
     - `extraFee`: Your extra fee that you set
     - `totalFee`: Total fee for your customer
@@ -23,61 +31,132 @@ pub struct Account {
     pub totalFee: String,
 }
 
+impl Account {
+    /**
+     * Returns the trading volume of the last month, denominated in USDT.
+     * An empty volume is returned as zero, as accounts without trades may not report one.
+     */
+    pub fn volume_usdt(&self) -> Result<Decimal, Error> {
+        Ok(parse_optional_amount("volume", &self.volume)?.unwrap_or_default())
+    }
+
+    /**
+     * Returns the volume in USDT still needed to reach the next account level.
+     * `thresholds` maps account levels to the monthly volume they require, as the API does not expose the tier table.
+     * Returns `None` if there is no higher level in the table or the volume cannot be parsed.
+     */
+    pub fn next_tier_gap(&self, thresholds: &[(i32, Decimal)]) -> Option<Decimal> {
+        let volume = self.volume_usdt().ok()?;
+        let (_, threshold) = thresholds
+            .iter()
+            .filter(|(level, _)| *level > self.level)
+            .min_by_key(|(level, _)| *level)?;
+        Some((*threshold - volume).max(Decimal::ZERO))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/**
+   ### Extra fee change made through a client.
+
+   - `at`: Time the change was accepted by the API
+   - `old`: Extra fee before the change, `None` if it could not be read from the account
+   - `new`: Extra fee after the change
+*/
+pub struct FeeChange {
+    pub at: SystemTime,
+    pub old: Option<Decimal>,
+    pub new: Decimal,
+}
+
 pub async fn get_account(client: &Client) -> Result<Account, Error> {
     // Define the URL.
     let path = "/account";
 
     // Make the request.
-    let response = reqwest::Client::new()
-        .get(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key())
-        .send()
-        .await?;
+    let response = client.send(client.get(path)?).await?;
 
-    match response.status() {
-        StatusCode::OK => {
-            let json: Value = response.json().await?;
-            match json.get("data") {
-                Some(data) => {
-                    let account: Account = serde_json::from_value(data.clone())?;
-                    Ok(account)
-                }
-                None => {
-                    let error: EasyBit = serde_json::from_value(json)?;
-                    log::error!("{:?}", error);
-                    Err(Error::ApiError(error))
-                }
-            }
-        }
-        _ => {
-            let error: EasyBit = response.json().await?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
-        }
+    handle_data_response(response).await
+}
+
+// Fields of the account response that this library relies on.
+const ACCOUNT_FIELDS: [&str; 5] = ["level", "volume", "fee", "extraFee", "totalFee"];
+
+/**
+ * Probes `/account` and checks that the response has the shape this library expects.
+ * Returns `Error::IncompatibleApiVersion` naming the mismatch if it does not. API errors, such as an invalid
+ * API key, are returned as they are.
+ */
+pub async fn check_compatibility(client: &Client) -> Result<(), Error> {
+    let incompatible =
+        |reason: String| Error::IncompatibleApiVersion(format!("/account {}", reason));
+
+    let response = client.send(client.get("/account")?).await?;
+    let json: Value = response
+        .json()
+        .await
+        .map_err(|error| incompatible(format!("did not return JSON: {}", error)))?;
+    let data = payload(json)?;
+
+    let Value::Object(fields) = &data else {
+        return Err(incompatible("did not return an object".to_string()));
+    };
+    let missing: Vec<&str> = ACCOUNT_FIELDS
+        .into_iter()
+        .filter(|field| !fields.contains_key(*field))
+        .collect();
+    if !missing.is_empty() {
+        return Err(incompatible(format!("is missing {}", missing.join(", "))));
     }
+    serde_json::from_value::<Account>(data)
+        .map_err(|error| incompatible(format!("has an unexpected shape: {}", error)))?;
+    Ok(())
 }
 
 pub async fn set_fee(client: &Client, fee: f64) -> Result<(), Error> {
+    // Reject fees the API would refuse with a generic message.
+    check_extra_fee(fee)?;
+
+    // Read the previous fee for the change history. Changes are made one at a time, so that concurrent calls do not
+    // record the same previous fee. A failed read is recorded as unknown and does not hold up the change.
+    let tracking = if client.get_fee_change_tracking() {
+        let guard = client.lock_fee_changes().await;
+        let old = match get_account(client).await {
+            Ok(account) => parse_amount("extraFee", &account.extraFee).ok(),
+            Err(error) => {
+                log::warn!("Failed to read the extra fee before changing it: {}", error);
+                None
+            }
+        };
+        Some((guard, old))
+    } else {
+        None
+    };
+
     // Define the URL.
     let path = "/setExtraFee";
 
     let field_name = "extraFee";
 
     // Make the request.
-    let response = reqwest::Client::new()
-        .post(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key())
-        .json(&serde_json::json!({field_name:fee}))
-        .send()
-        .await?;
+    let request = client
+        .post(path)?
+        .json(&serde_json::json!({field_name:fee}));
+    let response = client.send(request).await?;
 
     match response.status() {
-        StatusCode::OK => Ok(()),
-        _ => {
-            let error: EasyBit = response.json().await?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
+        StatusCode::OK => {
+            if let Some((_guard, old)) = tracking {
+                client.record_fee_change(FeeChange {
+                    at: SystemTime::now(),
+                    old,
+                    // Validated above, so the fee converts.
+                    new: Decimal::from_f64(fee).unwrap_or_default().normalize(),
+                });
+            }
+            Ok(())
         }
+        _ => Err(api_error(response).await),
     }
 }
 
@@ -87,6 +166,240 @@ mod tests {
     use super::*;
     use crate::client::Client;
     use std::env;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn account(volume: &str) -> Account {
+        Account {
+            level: 1,
+            volume: volume.to_string(),
+            fee: "0.004".to_string(),
+            extraFee: "0".to_string(),
+            totalFee: "0.004".to_string(),
+        }
+    }
+
+    async fn account_server(account: Value) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/account"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": account })),
+            )
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_check_compatibility() {
+        let server = account_server(serde_json::json!({
+            "level": 1,
+            "volume": "12500.75",
+            "fee": "0.004",
+            "extraFee": "0",
+            "totalFee": "0.004"
+        }))
+        .await;
+        let client = Client::new(server.uri(), "key".to_string());
+        assert!(check_compatibility(&client).await.is_ok());
+
+        let server = account_server(serde_json::json!({
+            "level": 1,
+            "monthlyVolume": "12500.75",
+            "fee": "0.004",
+            "extraFee": "0",
+            "totalFee": "0.004"
+        }))
+        .await;
+        let client = Client::new(server.uri(), "key".to_string());
+        match check_compatibility(&client).await {
+            Err(Error::IncompatibleApiVersion(reason)) => {
+                assert_eq!(reason, "/account is missing volume")
+            }
+            other => panic!(
+                "Expected an incompatible API version error, got {:?}",
+                other
+            ),
+        }
+
+        let server = account_server(serde_json::json!({
+            "level": "gold",
+            "volume": "12500.75",
+            "fee": "0.004",
+            "extraFee": "0",
+            "totalFee": "0.004"
+        }))
+        .await;
+        let client = Client::new(server.uri(), "key".to_string());
+        assert!(matches!(
+            check_compatibility(&client).await,
+            Err(Error::IncompatibleApiVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_volume_usdt() {
+        assert_eq!(
+            account("12500.75").volume_usdt().unwrap(),
+            Decimal::new(1250075, 2)
+        );
+        assert_eq!(account("").volume_usdt().unwrap(), Decimal::ZERO);
+        assert!(matches!(
+            account("n/a").volume_usdt(),
+            Err(Error::InvalidAmount(_))
+        ));
+    }
+
+    #[test]
+    fn test_next_tier_gap() {
+        let thresholds = [
+            (1, Decimal::ZERO),
+            (3, Decimal::new(100_000, 0)),
+            (2, Decimal::new(25_000, 0)),
+        ];
+
+        assert_eq!(
+            account("12500.75").next_tier_gap(&thresholds),
+            Some(Decimal::new(1249925, 2))
+        );
+
+        let mut top = account("150000");
+        top.level = 3;
+        assert_eq!(top.next_tier_gap(&thresholds), None);
+    }
+
+    #[tokio::test]
+    async fn test_set_fee_validation() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/setExtraFee"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        assert!(set_fee(&client, 0.004).await.is_ok());
+        for fee in [0.00015, 0.2] {
+            assert!(matches!(
+                set_fee(&client, fee).await,
+                Err(Error::InvalidFee { value, .. }) if value == fee
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_api_error_context() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/account"))
+            .respond_with(
+                ResponseTemplate::new(401)
+                    .insert_header("x-request-id", "req-123")
+                    .set_body_json(serde_json::json!({
+                        "success": 0,
+                        "errorCode": 401,
+                        "errorMessage": "Invalid API key"
+                    })),
+            )
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        match get_account(&client).await {
+            Err(Error::ApiError(error)) => {
+                assert_eq!(error.status, Some(StatusCode::UNAUTHORIZED));
+                assert_eq!(error.request_id.as_deref(), Some("req-123"));
+                assert_eq!(
+                    error.to_string(),
+                    "EasyBit 401: Invalid API key (HTTP 401, request ID req-123)"
+                );
+            }
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fee_change_history() {
+        let server = MockServer::start().await;
+        for (extra_fee, total_fee) in [("0.001", "0.005"), ("0.0025", "0.0065")] {
+            Mock::given(method("GET"))
+                .and(path("/account"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": {
+                        "level": 1,
+                        "volume": "12500.75",
+                        "fee": "0.004",
+                        "extraFee": extra_fee,
+                        "totalFee": total_fee
+                    }
+                })))
+                .up_to_n_times(1)
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("POST"))
+            .and(path("/setExtraFee"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        // Nothing is recorded unless tracking is enabled.
+        let client = Client::new(server.uri(), "key".to_string());
+        set_fee(&client, 0.002).await.unwrap();
+        assert!(client.fee_change_history().is_empty());
+
+        // The fee is read before every change, as it may have been changed elsewhere in between.
+        let client = Client::new(server.uri(), "key".to_string()).with_fee_change_tracking(true);
+        set_fee(&client, 0.002).await.unwrap();
+        set_fee(&client, 0.0035).await.unwrap();
+
+        let history = client.fee_change_history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            (history[0].old, history[0].new),
+            (Some(Decimal::new(1, 3)), Decimal::new(2, 3))
+        );
+        assert_eq!(
+            (history[1].old, history[1].new),
+            (Some(Decimal::new(25, 4)), Decimal::new(35, 4))
+        );
+        assert!(history[0].at <= history[1].at);
+    }
+
+    #[tokio::test]
+    async fn test_fee_change_history_unknown_old() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/account"))
+            .respond_with(ResponseTemplate::new(500).set_body_json(serde_json::json!({
+                "success": 0,
+                "errorCode": 500,
+                "errorMessage": "Internal Server Error"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/setExtraFee"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // A failed read of the account does not hold up the change.
+        let client = Client::new(server.uri(), "key".to_string()).with_fee_change_tracking(true);
+        set_fee(&client, 0.002).await.unwrap();
+
+        let history = client.fee_change_history();
+        assert_eq!(history.len(), 1);
+        assert_eq!((history[0].old, history[0].new), (None, Decimal::new(2, 3)));
+    }
 
     #[tokio::test]
     async fn test_get_account() {
@@ -0,0 +1,210 @@
+use std::str::FromStr;
+
+use rust_decimal::{prelude::FromPrimitive, Decimal, RoundingStrategy};
+
+use crate::Error;
+
+/**
+ * Parses an amount returned by the API as a string into an exact decimal.
+ */
+pub fn parse_amount(field: &str, value: &str) -> Result<Decimal, Error> {
+    Decimal::from_str(value.trim())
+        .map_err(|_| Error::InvalidAmount(format!("{} is not a valid amount: {:?}", field, value)))
+}
+
+/**
+ * Parses an amount that may not be set yet. An empty string is returned as `None` rather than as an error,
+ * as the API returns empty amounts for orders that are not finalized.
+ */
+pub fn parse_optional_amount(field: &str, value: &str) -> Result<Option<Decimal>, Error> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    parse_amount(field, value).map(Some)
+}
+
+// Largest extra fee the API accepts, as a fraction.
+const MAX_EXTRA_FEE: Decimal = Decimal::from_parts(1, 0, 0, false, 1);
+
+// Number of decimals of the extra fee step size, 0.0001.
+const EXTRA_FEE_DECIMALS: u32 = 4;
+
+/**
+ * Checks that an extra fee lies in the range 0-0.1 accepted by the API and is a multiple of the 0.0001 step size.
+ */
+pub fn check_extra_fee(fee: f64) -> Result<(), Error> {
+    let invalid = |reason: String| Error::InvalidFee { value: fee, reason };
+
+    let Some(value) = Decimal::from_f64(fee) else {
+        return Err(invalid("not a number".to_string()));
+    };
+    if value < Decimal::ZERO || value > MAX_EXTRA_FEE {
+        return Err(invalid(format!("outside the range 0-{}", MAX_EXTRA_FEE)));
+    }
+    if value.normalize().scale() > EXTRA_FEE_DECIMALS {
+        return Err(invalid(
+            "not a multiple of the step size 0.0001".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/**
+ * Divides two amounts, returning an error instead of panicking on a zero denominator.
+ */
+pub fn ratio(numerator: Decimal, denominator: Decimal) -> Result<Decimal, Error> {
+    numerator.checked_div(denominator).ok_or_else(|| {
+        Error::InvalidAmount(format!("cannot divide {} by {}", numerator, denominator))
+    })
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/**
+   ### Rounding mode of `round_to_decimals`.
+   - `Truncate`: Rounds towards zero, so that an amount is never overstated. This is the default
+   - `HalfUp`: Rounds to the nearest value, and a midpoint away from zero
+   - `HalfEven`: Rounds to the nearest value, and a midpoint to the even neighbour
+*/
+pub enum RoundingMode {
+    #[default]
+    Truncate,
+    HalfUp,
+    HalfEven,
+}
+
+/**
+ * Rounds an amount to the given number of decimals with the given rounding mode.
+ */
+pub fn round_to_decimals(amount: Decimal, decimals: u32, mode: RoundingMode) -> Decimal {
+    match mode {
+        RoundingMode::Truncate => amount.trunc_with_scale(decimals),
+        RoundingMode::HalfUp => {
+            amount.round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero)
+        }
+        RoundingMode::HalfEven => {
+            amount.round_dp_with_strategy(decimals, RoundingStrategy::MidpointNearestEven)
+        }
+    }
+}
+
+/**
+ * Checks that an amount, rounded to the decimals of its network, is not zero and not below the dust threshold.
+ */
+pub fn check_dust(amount: Decimal, decimals: u32, threshold: Decimal) -> Result<(), Error> {
+    let rounded = round_to_decimals(amount, decimals, RoundingMode::Truncate);
+    if rounded.is_zero() || rounded < threshold {
+        return Err(Error::AmountBelowDust {
+            amount: rounded,
+            threshold,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_optional_amount() {
+        assert_eq!(parse_optional_amount("sendAmount", "").unwrap(), None);
+        assert_eq!(parse_optional_amount("sendAmount", "  ").unwrap(), None);
+        assert_eq!(
+            parse_optional_amount("sendAmount", "0.1").unwrap(),
+            Some(Decimal::new(1, 1))
+        );
+        assert!(parse_optional_amount("sendAmount", "n/a").is_err());
+    }
+
+    #[test]
+    fn test_check_extra_fee() {
+        assert!(check_extra_fee(0.0).is_ok());
+        assert!(check_extra_fee(0.004).is_ok());
+        assert!(check_extra_fee(0.1).is_ok());
+
+        for fee in [-0.001, 0.1001, 0.00015, f64::NAN] {
+            assert!(
+                matches!(check_extra_fee(fee), Err(Error::InvalidFee { .. })),
+                "{} should be rejected",
+                fee
+            );
+        }
+
+        match check_extra_fee(0.00015) {
+            Err(Error::InvalidFee { value, reason }) => {
+                assert_eq!(value, 0.00015);
+                assert_eq!(reason, "not a multiple of the step size 0.0001");
+            }
+            other => panic!("Expected an invalid fee error, got {:?}", other),
+        }
+        match check_extra_fee(0.2) {
+            Err(Error::InvalidFee { value, reason }) => {
+                assert_eq!(value, 0.2);
+                assert_eq!(reason, "outside the range 0-0.1");
+            }
+            other => panic!("Expected an invalid fee error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_round_to_decimals() {
+        let amount = Decimal::new(12345, 4);
+        let negative = Decimal::new(-12345, 4);
+        let odd = Decimal::new(12355, 4);
+
+        assert_eq!(
+            round_to_decimals(amount, 3, RoundingMode::Truncate),
+            Decimal::new(1234, 3)
+        );
+        assert_eq!(
+            round_to_decimals(amount, 3, RoundingMode::HalfUp),
+            Decimal::new(1235, 3)
+        );
+        assert_eq!(
+            round_to_decimals(amount, 3, RoundingMode::HalfEven),
+            Decimal::new(1234, 3)
+        );
+
+        assert_eq!(
+            round_to_decimals(odd, 3, RoundingMode::Truncate),
+            Decimal::new(1235, 3)
+        );
+        assert_eq!(
+            round_to_decimals(odd, 3, RoundingMode::HalfUp),
+            Decimal::new(1236, 3)
+        );
+        assert_eq!(
+            round_to_decimals(odd, 3, RoundingMode::HalfEven),
+            Decimal::new(1236, 3)
+        );
+
+        // Rounding is symmetric around zero.
+        assert_eq!(
+            round_to_decimals(negative, 3, RoundingMode::Truncate),
+            Decimal::new(-1234, 3)
+        );
+        assert_eq!(
+            round_to_decimals(negative, 3, RoundingMode::HalfUp),
+            Decimal::new(-1235, 3)
+        );
+        assert_eq!(RoundingMode::default(), RoundingMode::Truncate);
+    }
+
+    #[test]
+    fn test_check_dust() {
+        let threshold = Decimal::new(546, 8);
+
+        // Rounds down to 0.00000545 on a network with 8 decimals.
+        match check_dust(Decimal::new(5459, 9), 8, threshold) {
+            Err(Error::AmountBelowDust { amount, .. }) => {
+                assert_eq!(amount, Decimal::new(545, 8))
+            }
+            other => panic!("Expected a dust error, got {:?}", other),
+        }
+
+        // Rounds down to zero on a network with 6 decimals, even without a threshold.
+        assert!(check_dust(Decimal::new(9, 7), 6, Decimal::ZERO).is_err());
+
+        assert!(check_dust(Decimal::new(1, 3), 8, threshold).is_ok());
+    }
+}
@@ -0,0 +1,273 @@
+/*!
+Blocking client for callers without an async runtime, enabled with the `blocking` feature.
+
+The methods mirror the async `client::Client` without the `async`. Each call is run to completion on a
+current-thread Tokio runtime owned by the client, the same way `reqwest::blocking` works internally.
+Do not call these methods from within an async runtime, as blocking there panics.
+*/
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+use tokio::runtime::Runtime;
+
+use crate::{
+    client::{
+        Account, ConsistencyReport, Currency, DisbursementItem, DisbursementOutcome,
+        EnrichedStatus, ExchangeRate, ExplorerUrls, ImpliedMidRate, KycRequirements, Network,
+        Order, OrderQuery, OrderStatus, Pair, PairIndex, PollConfig, Proof, Status, Summary, Timed,
+        TradingPair, Transaction, User,
+    },
+    Error,
+};
+use rust_decimal::Decimal;
+
+// Defines blocking methods that run the async method of the same name on the runtime.
+// Their docs link to the async method, which documents the behaviour and parameters.
+macro_rules! blocking {
+    ($($(#[$attribute:meta])* fn $name:ident(&self $(, $argument:ident: $type:ty)*) -> $output:ty;)*) => {
+        $(
+            #[doc = concat!("Blocking version of [`crate::client::Client::", stringify!($name), "`].")]
+            $(#[$attribute])*
+            pub fn $name(&self $(, $argument: $type)*) -> $output {
+                self.runtime.block_on(self.inner.$name($($argument),*))
+            }
+        )*
+    };
+}
+
+/**
+ * **Blocking client for interacting with the easybit.io API.**
+ */
+pub struct Client {
+    inner: crate::client::Client,
+    runtime: Runtime,
+}
+
+impl Client {
+    /**
+     * Create new blocking client with the given URL and API key. Requests time out after 30 seconds.
+     * Panics if the runtime of the client cannot be started, use `try_new` to handle this as an error instead.
+     */
+    pub fn new(url: String, api_key: String) -> Client {
+        Client::from_async(crate::client::Client::new(url, api_key))
+    }
+
+    /**
+     * Create new blocking client with the given URL and API key, validating the URL as `ClientBuilder::build` does.
+     * Returns `Error::InvalidBaseUrl` if the URL is not a valid base URL, and `Error::RuntimeError` if the runtime of
+     * the client cannot be started.
+     */
+    pub fn try_new(url: String, api_key: String) -> Result<Client, Error> {
+        Client::try_from_async(crate::client::Client::builder(url, api_key).build()?)
+    }
+
+    /**
+     * Create a blocking client from a configured async client, e.g. one made with `client::Client::builder`.
+     * Panics if the runtime of the client cannot be started, use `try_from_async` to handle this as an error instead.
+     */
+    pub fn from_async(inner: crate::client::Client) -> Client {
+        Client::try_from_async(inner)
+            .expect("failed to initialize the runtime of the blocking client")
+    }
+
+    /**
+     * Create a blocking client from a configured async client.
+     * Returns `Error::RuntimeError` if the runtime of the client cannot be started.
+     */
+    pub fn try_from_async(inner: crate::client::Client) -> Result<Client, Error> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(Error::RuntimeError)?;
+        Ok(Client { inner, runtime })
+    }
+
+    /**
+     * Get the async client that this client wraps, e.g. to read its configuration.
+     */
+    pub fn as_async(&self) -> &crate::client::Client {
+        &self.inner
+    }
+
+    blocking! {
+        fn get_account(&self) -> Result<Account, Error>;
+        fn get_account_timed(&self) -> Result<Timed<Account>, Error>;
+        fn check_compatibility(&self) -> Result<(), Error>;
+        fn set_fee(&self, fee: f64) -> Result<(), Error>;
+        fn get_currency_list(&self) -> Result<Vec<Currency>, Error>;
+        #[allow(clippy::type_complexity)]
+        fn get_currency_list_lenient(&self) -> Result<(Vec<Currency>, Vec<(usize, Error)>), Error>;
+        fn get_crypto_currencies(&self) -> Result<Vec<Currency>, Error>;
+        fn get_fiat_currencies(&self) -> Result<Vec<Currency>, Error>;
+        fn get_single_currency(&self, currency: String) -> Result<Currency, Error>;
+        fn get_receive_decimals(&self, currency: String, network: String) -> Result<i32, Error>;
+        fn get_explorer_urls(
+            &self,
+            currency: String,
+            network: String
+        ) -> Result<ExplorerUrls, Error>;
+        fn get_pair_list(&self) -> Result<Vec<String>, Error>;
+        #[allow(clippy::type_complexity)]
+        fn get_pair_list_parsed(&self) -> Result<(Vec<TradingPair>, Vec<(String, Error)>), Error>;
+        fn get_pairs_on_network(&self, network: &str) -> Result<Vec<TradingPair>, Error>;
+        fn build_pair_index(&self) -> Result<PairIndex, Error>;
+        fn check_data_consistency(&self) -> Result<ConsistencyReport, Error>;
+        fn get_pair_info(
+            &self,
+            send: String,
+            receive: String,
+            send_network: Option<String>,
+            receive_network: Option<String>,
+            amount_type: Option<String>
+        ) -> Result<Pair, Error>;
+        #[allow(clippy::too_many_arguments)]
+        fn get_exchange_rate(
+            &self,
+            send: String,
+            receive: String,
+            amount: f64,
+            send_network: Option<String>,
+            receive_network: Option<String>,
+            amount_type: Option<String>,
+            extra_fee_override: Option<f64>
+        ) -> Result<ExchangeRate, Error>;
+        fn round_trip_loss(&self, a: String, b: String, amount: f64) -> Result<Decimal, Error>;
+        fn implied_mid_rate(
+            &self,
+            a: String,
+            b: String,
+            amount: f64
+        ) -> Result<ImpliedMidRate, Error>;
+        fn solve_send_for_net_receive(
+            &self,
+            send: String,
+            receive: String,
+            target_net: Decimal,
+            send_network: Option<String>,
+            receive_network: Option<String>,
+            extra_fee_override: Option<f64>
+        ) -> Result<Decimal, Error>;
+        fn validate_address(
+            &self,
+            currency: String,
+            address: String,
+            network: Option<String>,
+            tag: Option<String>
+        ) -> Result<(), Error>;
+        fn validate_address_checked(
+            &self,
+            currency: String,
+            address: String,
+            network: Option<String>,
+            tag: Option<String>
+        ) -> Result<bool, Error>;
+        fn place_order(
+            &self,
+            transaction: Transaction,
+            user: User,
+            network: Network
+        ) -> Result<Order, Error>;
+        fn place_order_strict(
+            &self,
+            transaction: Transaction,
+            user: User,
+            network: Network
+        ) -> Result<Order, Error>;
+        fn disburse(&self, items: Vec<DisbursementItem>) -> Vec<DisbursementOutcome>;
+        fn get_order_status(&self, order_id: String) -> Result<Status, Error>;
+        fn get_order_statuses(&self, ids: &[String]) -> Result<Vec<Status>, Error>;
+        fn get_order_status_timed(&self, order_id: String) -> Result<Timed<Status>, Error>;
+        fn get_enriched_status(&self, order_id: String) -> Result<EnrichedStatus, Error>;
+        fn wait_for_order(&self, order_id: String, config: PollConfig) -> Result<Status, Error>;
+        fn get_order(&self, order_id: String) -> Result<Summary, Error>;
+        fn get_all_orders(
+            &self,
+            id: Option<String>,
+            limit: Option<String>,
+            date_from: Option<String>,
+            date_to: Option<String>,
+            sort_direction: Option<String>,
+            status: Option<String>
+        ) -> Result<Vec<Summary>, Error>;
+        fn query_orders(&self, query: OrderQuery) -> Result<Vec<Summary>, Error>;
+        fn get_incomplete_completions(&self) -> Result<Vec<Summary>, Error>;
+        fn get_stuck_orders(&self, grace: Duration) -> Result<Vec<Summary>, Error>;
+    }
+
+    /**
+     * Blocking version of [`crate::client::Client::find_order`].
+     */
+    pub fn find_order<F: Fn(&Summary) -> bool>(
+        &self,
+        predicate: F,
+        max_pages: usize,
+    ) -> Result<Option<Summary>, Error> {
+        self.runtime
+            .block_on(self.inner.find_order(predicate, max_pages))
+    }
+
+    blocking! {
+        fn find_recent_duplicate(
+            &self,
+            transaction: &Transaction,
+            window: Duration
+        ) -> Result<Option<Summary>, Error>;
+        fn orders_by_status(&self) -> Result<HashMap<OrderStatus, Vec<Summary>>, Error>;
+        fn earnings_by_pair(
+            &self,
+            from: SystemTime,
+            to: SystemTime
+        ) -> Result<HashMap<(String, String), Decimal>, Error>;
+        fn get_kyc_requirements(&self, order_id: String) -> Result<KycRequirements, Error>;
+        fn update_order_kyc(&self, proof: Proof) -> Result<(), Error>;
+        fn retry_kyc(&self, proof: Proof) -> Result<(), Error>;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::summary_json;
+    use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_blocking_client() {
+        // The mock server needs a runtime of its own, as the blocking client must not run inside one.
+        let runtime = Runtime::new().unwrap();
+        let server = runtime.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(path("/pairList"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({ "data": ["BTC_BTC_ETH_ETH"] })),
+                )
+                .mount(&server)
+                .await;
+            Mock::given(path("/orders"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": [summary_json("Complete", "0.1", "1.9")]
+                })))
+                .mount(&server)
+                .await;
+            server
+        });
+
+        let client = Client::new(server.uri(), "key".to_string());
+        assert_eq!(client.get_pair_list().unwrap(), vec!["BTC_BTC_ETH_ETH"]);
+        assert_eq!(client.as_async().get_url(), server.uri());
+        let found = client.find_order(|order| order.status == "Complete", 1);
+        assert_eq!(
+            found.unwrap().map(|order| order.id),
+            Some("order".to_string())
+        );
+
+        let client = Client::try_new(server.uri(), "key".to_string()).unwrap();
+        assert_eq!(client.get_pair_list().unwrap(), vec!["BTC_BTC_ETH_ETH"]);
+        assert!(matches!(
+            Client::try_new("localhost:8080".to_string(), "key".to_string()),
+            Err(Error::InvalidBaseUrl(_))
+        ));
+    }
+}
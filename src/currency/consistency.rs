@@ -0,0 +1,128 @@
+use crate::{
+    client::Client,
+    currency::{
+        info::{get_currency_list, Currency},
+        pair_list::{get_pair_list, parse_pair_list, TradingPair},
+    },
+    Error,
+};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/**
+   ### Inconsistencies between the pair list and the currency list.
+   - `unknown_currencies`: Pairs that send or receive a currency missing from the currency list
+   - `unknown_networks`: Pairs whose currencies are listed, but without the network the pair uses
+   - `malformed`: Entries of the pair list that could not be parsed
+*/
+pub struct ConsistencyReport {
+    pub unknown_currencies: Vec<TradingPair>,
+    pub unknown_networks: Vec<TradingPair>,
+    pub malformed: Vec<String>,
+}
+
+impl ConsistencyReport {
+    /**
+     * Returns true if every pair references listed currencies and networks.
+     */
+    pub fn is_consistent(&self) -> bool {
+        self.unknown_currencies.is_empty()
+            && self.unknown_networks.is_empty()
+            && self.malformed.is_empty()
+    }
+}
+
+// Finds a currency of the list by its code.
+fn find_currency<'a>(currencies: &'a [Currency], code: &str) -> Option<&'a Currency> {
+    currencies
+        .iter()
+        .find(|currency| currency.currency.eq_ignore_ascii_case(code))
+}
+
+/**
+ * Checks that every pair references currencies and networks of the currency list.
+ * Codes are matched case-insensitively.
+ */
+pub fn check_consistency(currencies: &[Currency], pairs: &[TradingPair]) -> ConsistencyReport {
+    let mut report = ConsistencyReport::default();
+
+    for pair in pairs {
+        let (Some(send), Some(receive)) = (
+            find_currency(currencies, &pair.send),
+            find_currency(currencies, &pair.receive),
+        ) else {
+            report.unknown_currencies.push(pair.clone());
+            continue;
+        };
+        if send.find_network(&pair.send_network).is_none()
+            || receive.find_network(&pair.receive_network).is_none()
+        {
+            report.unknown_networks.push(pair.clone());
+        }
+    }
+    report
+}
+
+pub async fn check_data_consistency(client: &Client) -> Result<ConsistencyReport, Error> {
+    let (currencies, pair_list) =
+        tokio::try_join!(get_currency_list(client), get_pair_list(client))?;
+
+    let (pairs, malformed) = parse_pair_list(pair_list);
+    let mut report = check_consistency(&currencies, &pairs);
+    report.malformed = malformed.into_iter().map(|(entry, _)| entry).collect();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{currency_json, network_json};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn currency(code: &str, network: &str) -> serde_json::Value {
+        currency_json(code, code, vec![network_json(network, network)])
+    }
+
+    #[tokio::test]
+    async fn test_check_data_consistency() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/currencyList"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [currency("BTC", "BTC"), currency("ETH", "ETH")]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/pairList"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [
+                    "BTC_BTC_ETH_ETH",
+                    "btc_btc_eth_eth",
+                    "BTC_BTC_XMR_XMR",
+                    "ETH_BSC_BTC_BTC",
+                    "BTC_ETH"
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string());
+        let report = check_data_consistency(&client).await.unwrap();
+
+        assert!(!report.is_consistent());
+        assert_eq!(
+            report.unknown_currencies,
+            vec!["BTC_BTC_XMR_XMR".parse::<TradingPair>().unwrap()]
+        );
+        assert_eq!(
+            report.unknown_networks,
+            vec!["ETH_BSC_BTC_BTC".parse::<TradingPair>().unwrap()]
+        );
+        assert_eq!(report.malformed, vec!["BTC_ETH"]);
+    }
+}
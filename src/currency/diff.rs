@@ -0,0 +1,154 @@
+use crate::currency::info::Currency;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/**
+   ### Changes between two snapshots of the currency list.
+   - `added`: Currency codes that are only in the new snapshot
+   - `removed`: Currency codes that are only in the old snapshot
+   - `networks_added`: `(currency, network)` codes of networks added to a currency present in both snapshots
+   - `networks_removed`: `(currency, network)` codes of networks removed from a currency present in both snapshots
+   - `status_changes`: Networks whose send or receive status flipped
+*/
+pub struct CurrencyDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub networks_added: Vec<(String, String)>,
+    pub networks_removed: Vec<(String, String)>,
+    pub status_changes: Vec<NetworkStatusChange>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/**
+   ### Status flip of a single network.
+   - `currency`: Currency code
+   - `network`: Network code
+   - `send_status`: New send status, if it changed
+   - `receive_status`: New receive status, if it changed
+*/
+pub struct NetworkStatusChange {
+    pub currency: String,
+    pub network: String,
+    pub send_status: Option<bool>,
+    pub receive_status: Option<bool>,
+}
+
+impl CurrencyDiff {
+    /**
+     * Returns true if the snapshots are equal in currencies, networks and statuses.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.networks_added.is_empty()
+            && self.networks_removed.is_empty()
+            && self.status_changes.is_empty()
+    }
+}
+
+/**
+ * Compares two snapshots of the currency list.
+ * Currencies and networks are matched by their codes.
+ */
+pub fn diff_currencies(old: &[Currency], new: &[Currency]) -> CurrencyDiff {
+    let mut diff = CurrencyDiff::default();
+
+    for currency in old {
+        if !new.iter().any(|other| other.currency == currency.currency) {
+            diff.removed.push(currency.currency.clone());
+        }
+    }
+
+    for currency in new {
+        let Some(previous) = old.iter().find(|other| other.currency == currency.currency) else {
+            diff.added.push(currency.currency.clone());
+            continue;
+        };
+
+        for network in &previous.networkList {
+            if currency.find_network(&network.network).is_none() {
+                diff.networks_removed
+                    .push((currency.currency.clone(), network.network.clone()));
+            }
+        }
+
+        for network in &currency.networkList {
+            let Some(before) = previous.find_network(&network.network) else {
+                diff.networks_added
+                    .push((currency.currency.clone(), network.network.clone()));
+                continue;
+            };
+
+            let send_status =
+                (before.sendStatus != network.sendStatus).then_some(network.sendStatus);
+            let receive_status =
+                (before.receiveStatus != network.receiveStatus).then_some(network.receiveStatus);
+            if send_status.is_some() || receive_status.is_some() {
+                diff.status_changes.push(NetworkStatusChange {
+                    currency: currency.currency.clone(),
+                    network: network.network.clone(),
+                    send_status,
+                    receive_status,
+                });
+            }
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{currency_json, network_json};
+
+    fn currency(code: &str, networks: &[(&str, bool, bool)]) -> Currency {
+        let networks = networks
+            .iter()
+            .map(|(network, send, receive)| {
+                let mut network = network_json(network, network);
+                network["sendStatus"] = (*send).into();
+                network["receiveStatus"] = (*receive).into();
+                network
+            })
+            .collect();
+        serde_json::from_value(currency_json(code, code, networks)).unwrap()
+    }
+
+    #[test]
+    fn test_diff_currencies() {
+        let old = vec![
+            currency("BTC", &[("BTC", true, true)]),
+            currency("USDT", &[("ETH", true, true), ("TRX", true, true)]),
+            currency("XMR", &[("XMR", true, true)]),
+        ];
+        let new = vec![
+            currency("BTC", &[("BTC", false, true)]),
+            currency("USDT", &[("ETH", true, true), ("BSC", true, true)]),
+            currency("SOL", &[("SOL", true, true)]),
+        ];
+
+        let diff = diff_currencies(&old, &new);
+
+        assert_eq!(diff.added, vec!["SOL"]);
+        assert_eq!(diff.removed, vec!["XMR"]);
+        assert_eq!(
+            diff.networks_added,
+            vec![("USDT".to_string(), "BSC".to_string())]
+        );
+        assert_eq!(
+            diff.networks_removed,
+            vec![("USDT".to_string(), "TRX".to_string())]
+        );
+        assert_eq!(
+            diff.status_changes,
+            vec![NetworkStatusChange {
+                currency: "BTC".to_string(),
+                network: "BTC".to_string(),
+                send_status: Some(false),
+                receive_status: None,
+            }]
+        );
+
+        assert!(diff_currencies(&old, &old).is_empty());
+    }
+}
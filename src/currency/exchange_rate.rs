@@ -1,10 +1,21 @@
-use reqwest::StatusCode;
-use serde::Deserialize;
-use serde_json::Value;
+use std::time::Duration;
 
-use crate::{client::Client, EasyBit, Error};
+use futures::{stream, Stream};
+use rust_decimal::{
+    prelude::{FromPrimitive, ToPrimitive},
+    Decimal,
+};
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug)]
+use crate::{
+    amount::{check_extra_fee, parse_amount, ratio, round_to_decimals, RoundingMode},
+    client::Client,
+    currency::{info::Network, network_selection::resolve_networks, pair_info::get_pair_info},
+    response::handle_data_response,
+    EasyBit, Error,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
 #[allow(non_snake_case)]
 /**
     ### Exchange rate information.
@@ -25,6 +36,103 @@ pub struct ExchangeRate {
     pub processingTime: String,
 }
 
+impl ExchangeRate {
+    /**
+     * Returns `rate` as an exact decimal.
+     */
+    pub fn rate_decimal(&self) -> Result<Decimal, Error> {
+        parse_amount("rate", &self.rate)
+    }
+
+    /**
+     * Returns `sendAmount` as an exact decimal.
+     */
+    pub fn send_amount_decimal(&self) -> Result<Decimal, Error> {
+        parse_amount("sendAmount", &self.sendAmount)
+    }
+
+    /**
+     * Returns `receiveAmount` as an exact decimal.
+     */
+    pub fn receive_amount_decimal(&self) -> Result<Decimal, Error> {
+        parse_amount("receiveAmount", &self.receiveAmount)
+    }
+
+    /**
+     * Returns `networkFee` as an exact decimal.
+     */
+    pub fn network_fee_decimal(&self) -> Result<Decimal, Error> {
+        parse_amount("networkFee", &self.networkFee)
+    }
+
+    /**
+     * Returns the all-in cost of the conversion as a percentage of the send amount, valued at `reference_rate`.
+     * The receive amount is `sendAmount * rate - networkFee`, and is compared to the `sendAmount * reference_rate`
+     * that a conversion at the reference market rate without fees would receive. Combines the spread and the fees.
+     * Returns `Error::InvalidAmount` if a field cannot be parsed, the reference rate is not positive or the send amount is zero.
+     */
+    pub fn total_cost_percentage(&self, reference_rate: Decimal) -> Result<Decimal, Error> {
+        if reference_rate <= Decimal::ZERO {
+            return Err(Error::InvalidAmount(format!(
+                "reference rate {} must be positive",
+                reference_rate
+            )));
+        }
+        let send_amount = self.send_amount_decimal()?;
+        let rate = self.rate_decimal()?;
+        let network_fee = self.network_fee_decimal()?;
+
+        let reference = send_amount * reference_rate;
+        let received = send_amount * rate - network_fee;
+        Ok(ratio(reference - received, reference)? * Decimal::ONE_HUNDRED)
+    }
+
+    /**
+     * Returns a warning if `receiveAmount` has more decimals than `receiveDecimals` of the receive network,
+     * as the customer cannot receive the amount exactly as quoted. Trailing zeros are not counted.
+     * Returns `None` if the precision is supported or the amount cannot be parsed.
+     */
+    pub fn warn_precision(&self, network: &Network) -> Option<String> {
+        let amount = parse_amount("receiveAmount", &self.receiveAmount)
+            .ok()?
+            .normalize();
+        let decimals = network.receiveDecimals.max(0) as u32;
+        if amount.scale() <= decimals {
+            return None;
+        }
+        Some(format!(
+            "Receive amount {} has {} decimals, but {} only supports {}. The amount received will be at most {}.",
+            amount,
+            amount.scale(),
+            network.network,
+            decimals,
+            round_to_decimals(amount, decimals, RoundingMode::Truncate)
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+/**
+    ### Mid-market rate implied by the quotes in both directions.
+
+    - `mid`: Geometric mean of the bid and ask rates, in units of the second currency per unit of the first
+    - `spread`: Difference between the ask and bid rates as a percentage of `mid`
+*/
+pub struct ImpliedMidRate {
+    pub mid: Decimal,
+    pub spread: Decimal,
+}
+
+/**
+ * Returns `Error::SameCurrency` if `send` and `receive` are the same currency, ignoring case and surrounding whitespace.
+ */
+pub fn check_distinct_currencies(send: &str, receive: &str) -> Result<(), Error> {
+    if send.trim().eq_ignore_ascii_case(receive.trim()) {
+        return Err(Error::SameCurrency(send.trim().to_uppercase()));
+    }
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn get_exchange_rate(
     client: &Client,
@@ -36,56 +144,636 @@ pub async fn get_exchange_rate(
     amount_type: Option<String>,
     extra_fee_override: Option<f64>,
 ) -> Result<ExchangeRate, Error> {
+    check_distinct_currencies(&send, &receive)?;
+    if let Some(fee) = extra_fee_override {
+        check_extra_fee(fee)?;
+    }
+
+    let (send_network, receive_network) =
+        resolve_networks(client, &send, &receive, send_network, receive_network).await?;
+
     // Define the path.
     let path = "/rate";
 
     // Make the request and set API key.
-    let response = reqwest::Client::new()
-        .get(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key())
-        .query(&[
-            ("send", send),
-            ("receive", receive),
-            ("amount", amount.to_string()),
-            ("sendNetwork", send_network.unwrap_or_default()),
-            ("receiveNetwork", receive_network.unwrap_or_default()),
-            ("amountType", amount_type.unwrap_or_default()),
-            (
-                "extraFeeOverride",
-                extra_fee_override.unwrap_or_default().to_string(),
-            ),
-        ])
-        .send()
-        .await?;
-
-    match response.status() {
-        StatusCode::OK => {
-            let json: Value = response.json().await?;
-            match json.get("data") {
-                Some(data) => {
-                    let exchange_rate: ExchangeRate = serde_json::from_value(data.clone())?;
-                    Ok(exchange_rate)
-                }
-                None => {
-                    let error: EasyBit = serde_json::from_value(json)?;
-                    log::error!("{:?}", error);
-                    Err(Error::ApiError(error))
-                }
+    let request = client.get(path)?.query(&[
+        ("send", send),
+        ("receive", receive),
+        ("amount", amount.to_string()),
+        ("sendNetwork", send_network.unwrap_or_default()),
+        ("receiveNetwork", receive_network.unwrap_or_default()),
+        ("amountType", amount_type.unwrap_or_default()),
+        (
+            "extraFeeOverride",
+            extra_fee_override.unwrap_or_default().to_string(),
+        ),
+    ]);
+    let response = client.send(request).await?;
+
+    handle_data_response(response).await
+}
+
+/**
+ * Quotes `send` to `receive` for `amount` every `interval`, starting immediately.
+ * Every result is yielded, including errors. Polling stops when the stream is dropped.
+ */
+pub fn rate_stream(
+    client: &Client,
+    send: String,
+    receive: String,
+    amount: f64,
+    interval: Duration,
+) -> impl Stream<Item = Result<ExchangeRate, Error>> + '_ {
+    stream::unfold(true, move |first_poll| {
+        let (send, receive) = (send.clone(), receive.clone());
+        async move {
+            if !first_poll {
+                tokio::time::sleep(interval).await;
             }
+            let rate =
+                get_exchange_rate(client, send, receive, amount, None, None, None, None).await;
+            Some((rate, false))
         }
-        _ => {
-            let error: EasyBit = response.json().await?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
+    })
+}
+
+/**
+ * Quotes `send` to `receive` for `amount` and the reverse direction for receiving `amount` back, concurrently.
+ * Returns the percentage of `amount` lost on a full round trip, including fees on both legs.
+ */
+pub async fn round_trip_loss(
+    client: &Client,
+    send: String,
+    receive: String,
+    amount: f64,
+) -> Result<Decimal, Error> {
+    let (forward, reverse) = tokio::join!(
+        get_exchange_rate(
+            client,
+            send.clone(),
+            receive.clone(),
+            amount,
+            None,
+            None,
+            None,
+            None
+        ),
+        get_exchange_rate(
+            client,
+            receive.clone(),
+            send.clone(),
+            amount,
+            None,
+            None,
+            Some("receive".to_string()),
+            None
+        ),
+    );
+    let forward = forward.map_err(|error| unsupported_direction(error, &send, &receive))?;
+    let reverse = reverse.map_err(|error| unsupported_direction(error, &receive, &send))?;
+
+    // The forward leg yields `received`, while the reverse leg needs `required` to get `amount` back.
+    let received = parse_amount("receiveAmount", &forward.receiveAmount)?;
+    let required = parse_amount("sendAmount", &reverse.sendAmount)?;
+    Ok((Decimal::ONE - ratio(received, required)?) * Decimal::ONE_HUNDRED)
+}
+
+/**
+ * Quotes `a` to `b` for `amount` and `b` to `a` for receiving `amount` back, concurrently.
+ * The forward leg gives the bid and the reverse leg the ask, both as effective rates including fees.
+ * Returns their geometric mean as the implied mid-market rate, and the spread between them.
+ */
+pub async fn implied_mid_rate(
+    client: &Client,
+    a: String,
+    b: String,
+    amount: f64,
+) -> Result<ImpliedMidRate, Error> {
+    let (forward, reverse) = tokio::join!(
+        get_exchange_rate(client, a.clone(), b.clone(), amount, None, None, None, None),
+        get_exchange_rate(
+            client,
+            b.clone(),
+            a.clone(),
+            amount,
+            None,
+            None,
+            Some("receive".to_string()),
+            None
+        ),
+    );
+    let forward = forward.map_err(|error| unsupported_direction(error, &a, &b))?;
+    let reverse = reverse.map_err(|error| unsupported_direction(error, &b, &a))?;
+
+    // Both rates are in units of `b` per unit of `a`.
+    let bid = ratio(
+        parse_amount("receiveAmount", &forward.receiveAmount)?,
+        parse_amount("sendAmount", &forward.sendAmount)?,
+    )?;
+    let ask = ratio(
+        parse_amount("sendAmount", &reverse.sendAmount)?,
+        parse_amount("receiveAmount", &reverse.receiveAmount)?,
+    )?;
+
+    // Decimal has no square root without the maths feature, so the mean is taken in floating point.
+    let mid = (bid * ask)
+        .to_f64()
+        .filter(|product| *product > 0.0)
+        .and_then(|product| Decimal::from_f64(product.sqrt()))
+        .ok_or_else(|| {
+            Error::InvalidAmount(format!("no mid rate between bid {} and ask {}", bid, ask))
+        })?;
+    Ok(ImpliedMidRate {
+        mid,
+        spread: ratio(ask - bid, mid)? * Decimal::ONE_HUNDRED,
+    })
+}
+
+// Quotes beyond which the solver gives up.
+const MAX_SOLVE_ITERATIONS: usize = 12;
+
+// Relative distance from the target at which a solution is accepted.
+const SOLVE_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 8);
+
+// Quotes the net receive amount for sending `amount`.
+async fn quote_receive(
+    client: &Client,
+    send: &str,
+    receive: &str,
+    amount: Decimal,
+    send_network: &Option<String>,
+    receive_network: &Option<String>,
+    extra_fee_override: Option<f64>,
+) -> Result<Decimal, Error> {
+    let amount = amount
+        .to_f64()
+        .ok_or_else(|| Error::InvalidAmount(format!("{} cannot be quoted", amount)))?;
+    let rate = get_exchange_rate(
+        client,
+        send.to_string(),
+        receive.to_string(),
+        amount,
+        send_network.clone(),
+        receive_network.clone(),
+        None,
+        extra_fee_override,
+    )
+    .await?;
+    parse_amount("receiveAmount", &rate.receiveAmount)
+}
+
+/**
+ * Solves for the amount of `send` that receives `target` of `receive`, net of the exchange rate, the network fee
+ * and the extra fee. Quotes are refined with the secant method until the quoted receive amount is within a relative
+ * tolerance of `target`. Returns `Error::InvalidAmount` if the target is not positive, the solver does not converge
+ * or the solved amount is outside the limits of the pair.
+ */
+#[allow(clippy::too_many_arguments)]
+pub async fn solve_send_for_net_receive(
+    client: &Client,
+    send: String,
+    receive: String,
+    target: Decimal,
+    send_network: Option<String>,
+    receive_network: Option<String>,
+    extra_fee_override: Option<f64>,
+) -> Result<Decimal, Error> {
+    if target <= Decimal::ZERO {
+        return Err(Error::InvalidAmount(format!(
+            "target receive amount {} must be positive",
+            target
+        )));
+    }
+
+    let (send_network, receive_network) =
+        resolve_networks(client, &send, &receive, send_network, receive_network).await?;
+    let pair = get_pair_info(
+        client,
+        send.clone(),
+        receive.clone(),
+        send_network.clone(),
+        receive_network.clone(),
+        None,
+    )
+    .await?;
+    let minimum = parse_amount("minimumAmount", &pair.minimumAmount)?;
+    let maximum = parse_amount("maximumAmount", &pair.maximumAmount)?;
+
+    let quote = |amount| {
+        quote_receive(
+            client,
+            &send,
+            &receive,
+            amount,
+            &send_network,
+            &receive_network,
+            extra_fee_override,
+        )
+    };
+
+    // Start at the minimum, then extrapolate from its rate to a first estimate.
+    let mut previous = if minimum > Decimal::ZERO {
+        minimum
+    } else {
+        Decimal::ONE
+    };
+    let mut previous_received = quote(previous).await?;
+    let mut amount = if previous_received > Decimal::ZERO {
+        previous * ratio(target, previous_received)?
+    } else {
+        previous * Decimal::TWO
+    };
+
+    for _ in 0..MAX_SOLVE_ITERATIONS {
+        let received = quote(amount).await?;
+        if ((received - target) / target).abs() <= SOLVE_TOLERANCE {
+            if amount < minimum || amount > maximum {
+                return Err(Error::InvalidAmount(format!(
+                    "{} {} is outside the limits of the pair [{}, {}]",
+                    amount, send, minimum, maximum
+                )));
+            }
+            return Ok(amount.normalize());
         }
+
+        let slope = ratio(received - previous_received, amount - previous)?;
+        (previous, previous_received) = (amount, received);
+        amount += ratio(target - received, slope)?;
+    }
+
+    Err(Error::InvalidAmount(format!(
+        "no amount of {} receives {} {} within {} quotes",
+        send, target, receive, MAX_SOLVE_ITERATIONS
+    )))
+}
+
+// Adds the direction that failed to an API error.
+fn unsupported_direction(error: Error, send: &str, receive: &str) -> Error {
+    match error {
+        Error::ApiError(error) => Error::ApiError(EasyBit {
+            errorMessage: format!(
+                "Direction {} to {} is not supported: {}",
+                send, receive, error.errorMessage
+            ),
+            ..error
+        }),
+        other => other,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::client::Client;
+    use crate::{client::Client, test_fixtures::trc20_network_json};
+    use futures::StreamExt;
     use std::env;
+    use std::str::FromStr;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, Request, Respond, ResponseTemplate,
+    };
+
+    fn rate(send_amount: &str, receive_amount: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": {
+                "rate": "0",
+                "sendAmount": send_amount,
+                "receiveAmount": receive_amount,
+                "networkFee": "0",
+                "confirmations": 2,
+                "processingTime": "5-10 min"
+            }
+        }))
+    }
+
+    #[test]
+    fn test_total_cost_percentage() {
+        let quote = ExchangeRate {
+            rate: "19.8".to_string(),
+            sendAmount: "1".to_string(),
+            receiveAmount: "19.79".to_string(),
+            networkFee: "0.01".to_string(),
+            confirmations: 2,
+            processingTime: "5-10 min".to_string(),
+        };
+
+        // 19.79 ETH received, 20 ETH at the reference rate.
+        assert_eq!(
+            quote.total_cost_percentage(Decimal::new(20, 0)).unwrap(),
+            Decimal::new(105, 2)
+        );
+        assert!(quote.total_cost_percentage(Decimal::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_decimal_accessors() {
+        let quote = ExchangeRate {
+            rate: "0.000000000123456789".to_string(),
+            sendAmount: "123456789012.123456789".to_string(),
+            receiveAmount: "15.241578753238836".to_string(),
+            networkFee: "n/a".to_string(),
+            confirmations: 2,
+            processingTime: "5-10 min".to_string(),
+        };
+
+        // Both amounts are beyond the precision of an f64.
+        assert_eq!(quote.rate_decimal().unwrap(), Decimal::new(123456789, 18));
+        assert_eq!(
+            quote.send_amount_decimal().unwrap().to_string(),
+            "123456789012.123456789"
+        );
+        assert_eq!(
+            quote.receive_amount_decimal().unwrap(),
+            Decimal::new(15241578753238836, 15)
+        );
+        assert!(matches!(
+            quote.network_fee_decimal(),
+            Err(Error::InvalidAmount(_))
+        ));
+    }
+
+    #[test]
+    fn test_warn_precision() {
+        let network: Network = serde_json::from_value(trc20_network_json()).unwrap();
+        let quote = |receive_amount: &str| ExchangeRate {
+            rate: "60000".to_string(),
+            sendAmount: "0.1".to_string(),
+            receiveAmount: receive_amount.to_string(),
+            networkFee: "1".to_string(),
+            confirmations: 2,
+            processingTime: "5-10 min".to_string(),
+        };
+
+        let warning = quote("5999.12345678").warn_precision(&network).unwrap();
+        assert!(warning.contains("8 decimals"));
+        assert!(warning.contains("at most 5999.123456"));
+
+        assert_eq!(quote("5999.123456").warn_precision(&network), None);
+        assert_eq!(quote("5999.12345600").warn_precision(&network), None);
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_loss() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rate"))
+            .and(query_param("send", "BTC"))
+            .respond_with(rate("1", "19.8"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/rate"))
+            .and(query_param("send", "ETH"))
+            .and(query_param("amountType", "receive"))
+            .respond_with(rate("20.2", "1"))
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string());
+        let loss = round_trip_loss(&client, "BTC".to_string(), "ETH".to_string(), 1.0)
+            .await
+            .unwrap();
+
+        // 19.8 ETH received, 20.2 ETH needed to get 1 BTC back.
+        assert_eq!(loss.round_dp(4), Decimal::new(19802, 4));
+    }
+
+    #[tokio::test]
+    async fn test_implied_mid_rate() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rate"))
+            .and(query_param("send", "BTC"))
+            .respond_with(rate("1", "19.8"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/rate"))
+            .and(query_param("send", "ETH"))
+            .and(query_param("amountType", "receive"))
+            .respond_with(rate("20", "1"))
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string());
+        let implied = implied_mid_rate(&client, "BTC".to_string(), "ETH".to_string(), 1.0)
+            .await
+            .unwrap();
+
+        // Bid 19.8 and ask 20 ETH per BTC, so the mid is the square root of 396.
+        assert_eq!(implied.mid.round_dp(6), Decimal::new(19899749, 6));
+        assert_eq!(implied.spread.round_dp(4), Decimal::new(10050, 4));
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_loss_unsupported_direction() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rate"))
+            .and(query_param("send", "BTC"))
+            .respond_with(rate("1", "19.8"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/rate"))
+            .and(query_param("send", "ETH"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "success": 0,
+                "errorCode": 1005,
+                "errorMessage": "Pair not available"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string());
+        match round_trip_loss(&client, "BTC".to_string(), "ETH".to_string(), 1.0).await {
+            Err(Error::ApiError(error)) => {
+                assert_eq!(error.errorCode, 1005);
+                assert!(error.errorMessage.starts_with("Direction ETH to BTC"));
+            }
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+    }
+
+    // Quotes 19.8 ETH per BTC, less a fixed network fee of 0.005 ETH.
+    struct LinearRate;
+
+    impl Respond for LinearRate {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let amount = request
+                .url
+                .query_pairs()
+                .find(|(key, _)| key == "amount")
+                .map(|(_, value)| Decimal::from_str(&value).unwrap())
+                .unwrap();
+            let received = amount * Decimal::new(198, 1) - Decimal::new(5, 3);
+            rate(&amount.to_string(), &received.to_string())
+        }
+    }
+
+    async fn linear_server(maximum: &str) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/pairInfo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {
+                    "minimumAmount": "0.001",
+                    "maximumAmount": maximum,
+                    "networkFee": "0.005",
+                    "confirmations": 2,
+                    "processingTime": "5-10 min"
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/rate"))
+            .respond_with(LinearRate)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_solve_send_for_net_receive() {
+        let server = linear_server("10").await;
+        let client = Client::new(server.uri(), "key".to_string());
+        let target = Decimal::new(5, 0);
+
+        let amount = solve_send_for_net_receive(
+            &client,
+            "BTC".to_string(),
+            "ETH".to_string(),
+            target,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let received = amount * Decimal::new(198, 1) - Decimal::new(5, 3);
+        assert!((received - target).abs() < Decimal::new(1, 6));
+    }
+
+    #[tokio::test]
+    async fn test_solve_send_for_net_receive_above_maximum() {
+        let server = linear_server("0.1").await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        match solve_send_for_net_receive(
+            &client,
+            "BTC".to_string(),
+            "ETH".to_string(),
+            Decimal::new(5, 0),
+            None,
+            None,
+            None,
+        )
+        .await
+        {
+            Err(Error::InvalidAmount(message)) => assert!(message.contains("outside the limits")),
+            other => panic!("Expected an invalid amount error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_exchange_rate_same_currency() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rate"))
+            .respond_with(rate("1", "19.8"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+        let quote = |send: &str, receive: &str| {
+            get_exchange_rate(
+                &client,
+                send.to_string(),
+                receive.to_string(),
+                1.0,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+
+        match quote("btc", " BTC").await {
+            Err(Error::SameCurrency(currency)) => assert_eq!(currency, "BTC"),
+            other => panic!("Expected a same currency error, got {:?}", other),
+        }
+        assert!(quote("BTC", "ETH").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rate_stream() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rate"))
+            .respond_with(rate("1", "19.8"))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/rate"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "success": 0,
+                "errorCode": 1006,
+                "errorMessage": "Rate unavailable"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        let rates: Vec<Result<ExchangeRate, Error>> = rate_stream(
+            &client,
+            "BTC".to_string(),
+            "ETH".to_string(),
+            1.0,
+            Duration::from_millis(10),
+        )
+        .take(3)
+        .collect()
+        .await;
+
+        assert_eq!(rates.len(), 3);
+        assert_eq!(rates[0].as_ref().unwrap().receiveAmount, "19.8");
+        assert_eq!(rates[1].as_ref().unwrap().receiveAmount, "19.8");
+        assert!(matches!(rates[2], Err(Error::ApiError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_exchange_rate_extra_fee_override() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rate"))
+            .respond_with(rate("1", "19.8"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+        let quote = |extra_fee_override: f64| {
+            get_exchange_rate(
+                &client,
+                "BTC".to_string(),
+                "ETH".to_string(),
+                1.0,
+                None,
+                None,
+                None,
+                Some(extra_fee_override),
+            )
+        };
+
+        // Rejected before a request is made.
+        assert!(matches!(quote(-0.01).await, Err(Error::InvalidFee { .. })));
+        assert!(matches!(quote(0.2).await, Err(Error::InvalidFee { .. })));
+        assert!(quote(0.004).await.is_ok());
+    }
 
     #[tokio::test]
     async fn test_get_exchange_rate() {
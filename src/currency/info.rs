@@ -1,10 +1,54 @@
-use reqwest::StatusCode;
-use serde::Deserialize;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::{client::Client, EasyBit, Error};
+use crate::{
+    client::Client,
+    response::{deserialize, handle_data_response},
+    EasyBit, Error,
+};
+
+// ISO 4217 codes of fiat currencies, as the API does not flag fiat currencies itself.
+const FIAT_CURRENCIES: [&str; 38] = [
+    "AED", "ARS", "AUD", "BGN", "BRL", "CAD", "CHF", "CNY", "CZK", "DKK", "EUR", "GBP", "HKD",
+    "HUF", "IDR", "ILS", "INR", "JPY", "KES", "KRW", "MXN", "MYR", "NGN", "NOK", "NZD", "PHP",
+    "PLN", "RON", "RUB", "SAR", "SEK", "SGD", "THB", "TRY", "UAH", "USD", "VND", "ZAR",
+];
+
+// Average block time in milliseconds per network code. These are long-run averages of the chains
+// themselves and do not account for congestion or for the time the exchange needs to process an order.
+const BLOCK_TIMES: [(&str, u64); 24] = [
+    ("ADA", 20_000),
+    ("ARBITRUM", 250),
+    ("ATOM", 6_000),
+    ("AVAXC", 2_000),
+    ("BASE", 2_000),
+    ("BCH", 600_000),
+    ("BSC", 3_000),
+    ("BTC", 600_000),
+    ("DASH", 150_000),
+    ("DOGE", 60_000),
+    ("DOT", 6_000),
+    ("ETC", 13_000),
+    ("ETH", 12_000),
+    ("FTM", 1_000),
+    ("LTC", 150_000),
+    ("MATIC", 2_000),
+    ("OPTIMISM", 2_000),
+    ("POLYGON", 2_000),
+    ("SOL", 400),
+    ("TON", 5_000),
+    ("TRX", 3_000),
+    ("XLM", 5_000),
+    ("XMR", 120_000),
+    ("XRP", 4_000),
+];
+
+// Block time assumed for networks that are not in the table.
+const DEFAULT_BLOCK_TIME: u64 = 60_000;
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[allow(non_snake_case)]
 /**
     ### Currency information.
@@ -23,7 +67,7 @@ pub struct Currency {
     pub networkList: Vec<Network>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[allow(non_snake_case)]
 /**
     - `network`: Network code
@@ -60,29 +104,221 @@ pub struct Network {
     pub explorerContract: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+/**
+    ### Explorer URLs for a currency on a single network.
+
+    - `explorer`: URL for the explorer
+    - `explorer_hash`: URL for the hash explorer
+    - `explorer_address`: URL for the address explorer
+    - `explorer_contract`: URL for the contract explorer, if the currency is a token
+*/
+pub struct ExplorerUrls {
+    pub explorer: String,
+    pub explorer_hash: String,
+    pub explorer_address: String,
+    pub explorer_contract: Option<String>,
+}
+
+impl ExplorerUrls {
+    /**
+     * Returns the explorer URL of a transaction.
+     */
+    pub fn transaction_url(&self, hash: &str) -> String {
+        format!("{}{}", self.explorer_hash, hash)
+    }
+
+    /**
+     * Returns the explorer URL of an address.
+     */
+    pub fn address_url(&self, address: &str) -> String {
+        format!("{}{}", self.explorer_address, address)
+    }
+}
+
+impl Network {
+    /**
+     * Returns the average block time of this network from a built-in table of long-run chain averages.
+     * Networks that are not in the table are assumed to produce a block every minute.
+     */
+    pub fn average_block_time(&self) -> Duration {
+        let block_time = BLOCK_TIMES
+            .iter()
+            .find(|(network, _)| self.network.eq_ignore_ascii_case(network))
+            .map_or(DEFAULT_BLOCK_TIME, |(_, block_time)| *block_time);
+        Duration::from_millis(block_time)
+    }
+
+    /**
+     * Estimates how long the confirmations of a deposit on this network take, as a `(minimum, maximum)` window.
+     * Multiplies `confirmationsMinimum` and `confirmationsMaximum` by the average block time of the network.
+     * Block times vary with congestion, so the window is an estimate and excludes the processing time of the exchange.
+     */
+    pub fn estimated_processing_window(&self) -> (Duration, Duration) {
+        let block_time = self.average_block_time();
+        let blocks = |confirmations: i32| block_time * confirmations.max(0) as u32;
+        (
+            blocks(self.confirmationsMinimum),
+            blocks(self.confirmationsMaximum),
+        )
+    }
+
+    /**
+     * Estimates the time until a deposit with `confirmations_seen` confirmations reaches `confirmationsMinimum`.
+     * Multiplies the remaining confirmations by the average block time of the network, see `average_block_time`.
+     * Returns zero once the minimum is reached.
+     */
+    pub fn confirmation_eta(&self, confirmations_seen: i32) -> Duration {
+        let remaining = self
+            .confirmationsMinimum
+            .saturating_sub(confirmations_seen.max(0))
+            .max(0);
+        self.average_block_time() * remaining as u32
+    }
+}
+
+impl Currency {
+    /**
+     * Returns true if this is a fiat currency.
+     * The API does not flag fiat currencies, so the currency code is checked against a curated list of ISO 4217 codes.
+     */
+    pub fn is_fiat(&self) -> bool {
+        FIAT_CURRENCIES.contains(&self.currency.to_uppercase().as_str())
+    }
+
+    /**
+     * Returns the networks of this currency in a sensible display order. The sort key is, in order:
+     * 1. The default network (`isDefault`)
+     * 2. Networks that can both send and receive (`sendStatus` and `receiveStatus`)
+     * 3. All other networks
+     *
+     * Networks of the same rank keep their order in `networkList`.
+     */
+    pub fn networks_by_priority(&self) -> Vec<&Network> {
+        let mut networks: Vec<&Network> = self.networkList.iter().collect();
+        networks.sort_by_key(|network| {
+            (
+                !network.isDefault,
+                !(network.sendStatus && network.receiveStatus),
+            )
+        });
+        networks
+    }
+
+    /**
+     * Finds a network of this currency by its network code.
+     */
+    pub fn find_network(&self, network: &str) -> Option<&Network> {
+        self.networkList
+            .iter()
+            .find(|candidate| candidate.network.eq_ignore_ascii_case(network))
+    }
+
+    /**
+     * Finds a network of this currency by its network code, returning an `ApiError` with code 404 if it is not supported.
+     */
+    pub fn network(&self, network: &str) -> Result<&Network, Error> {
+        self.find_network(network).ok_or_else(|| {
+            Error::ApiError(EasyBit::new(
+                404,
+                format!("Network {} not found for {}", network, self.currency),
+            ))
+        })
+    }
+
+    /**
+     * Returns the explorer URLs for the given network of this currency.
+     */
+    pub fn explorer_urls(&self, network: &str) -> Result<ExplorerUrls, Error> {
+        let network = self.network(network)?;
+        Ok(ExplorerUrls {
+            explorer: network.explorer.clone(),
+            explorer_hash: network.explorerHash.clone(),
+            explorer_address: network.explorerAddress.clone(),
+            explorer_contract: network.explorerContract.clone(),
+        })
+    }
+}
+
+/**
+ * Merges currencies that appear more than once with the same code into the first entry.
+ * The top-level fields of the last entry win, and the network lists are merged, with the last entry of a network
+ * code winning. Logs a warning for every duplicate.
+ */
+pub fn dedupe_currencies(currencies: Vec<Currency>) -> Vec<Currency> {
+    let mut merged: Vec<Currency> = Vec::with_capacity(currencies.len());
+    for currency in currencies {
+        let Some(existing) = merged
+            .iter_mut()
+            .find(|existing| existing.currency.eq_ignore_ascii_case(&currency.currency))
+        else {
+            merged.push(currency);
+            continue;
+        };
+
+        log::warn!(
+            "Currency {} is listed more than once, merging its networks",
+            currency.currency
+        );
+        let mut networks = std::mem::take(&mut existing.networkList);
+        for network in currency.networkList.iter() {
+            match networks
+                .iter_mut()
+                .find(|existing| existing.network.eq_ignore_ascii_case(&network.network))
+            {
+                Some(existing) => *existing = network.clone(),
+                None => networks.push(network.clone()),
+            }
+        }
+        *existing = Currency {
+            networkList: networks,
+            ..currency
+        };
+    }
+    merged
+}
+
 pub async fn get_currency_list(client: &Client) -> Result<Vec<Currency>, Error> {
     // Define the URL.
     let path = "/currencyList";
 
     // Make the request and set API key.
-    let response = reqwest::Client::new()
-        .get(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key())
-        .send()
-        .await?;
-
-    let json: Value = response.json().await?;
-    match json.get("data") {
-        Some(data) => {
-            let currency_list: Vec<Currency> = serde_json::from_value(data.clone())?;
-            Ok(currency_list)
-        }
-        None => {
-            let error: EasyBit = serde_json::from_value(json)?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
+    let response = client.send(client.get(path)?).await?;
+
+    let currency_list: Vec<Currency> = handle_data_response(response).await?;
+    if client.get_dedupe_currencies() {
+        return Ok(dedupe_currencies(currency_list));
+    }
+    Ok(currency_list)
+}
+
+/**
+ * Retrieves the currency list, deserializing each currency on its own so that one malformed currency does not lose
+ * the whole list. Malformed currencies are returned apart with their index in the list and their error.
+ */
+pub async fn get_currency_list_lenient(
+    client: &Client,
+) -> Result<(Vec<Currency>, Vec<(usize, Error)>), Error> {
+    // Define the URL.
+    let path = "/currencyList";
+
+    // Make the request and set API key.
+    let response = client.send(client.get(path)?).await?;
+
+    let entries: Vec<Value> = handle_data_response(response).await?;
+
+    let mut currencies = Vec::new();
+    let mut malformed = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        match deserialize::<Currency>(entry) {
+            Ok(currency) => currencies.push(currency),
+            Err(error) => malformed.push((index, error)),
         }
     }
+    if client.get_dedupe_currencies() {
+        currencies = dedupe_currencies(currencies);
+    }
+    Ok((currencies, malformed))
 }
 
 pub async fn get_single_currency(client: &Client, currency: String) -> Result<Currency, Error> {
@@ -90,49 +326,176 @@ pub async fn get_single_currency(client: &Client, currency: String) -> Result<Cu
     let path = format!("/currencyList?currency={}", currency);
 
     // Make the request and set API key.
-    let response = reqwest::Client::new()
-        .get(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key())
-        .send()
-        .await?;
-
-    match response.status() {
-        StatusCode::OK => {
-            // Convert the response to an object. Do not use unwrap.
-            let json: Value = response.json().await?;
-            match json.get("data") {
-                Some(data) => {
-                    // Print the data.
-                    let currency: Vec<Currency> = serde_json::from_value(data.clone())?;
-
-                    if currency.is_empty() {
-                        return Err(Error::ApiError(EasyBit {
-                            errorMessage: "Currency not found".to_string(),
-                            errorCode: 404,
-                        }));
-                    }
-                    Ok(currency[0].clone())
-                }
-                None => {
-                    let error: EasyBit = serde_json::from_value(json)?;
-                    log::error!("{:?}", error);
-                    Err(Error::ApiError(error))
-                }
-            }
-        }
-        _ => {
-            let error: EasyBit = response.json().await?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
-        }
-    }
+    let response = client.send(client.get(&path)?).await?;
+
+    let currency: Vec<Currency> = handle_data_response(response).await?;
+    currency
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::ApiError(EasyBit::new(404, "Currency not found".to_string())))
+}
+
+pub async fn get_explorer_urls(
+    client: &Client,
+    currency: String,
+    network: String,
+) -> Result<ExplorerUrls, Error> {
+    let currency = get_single_currency(client, currency).await?;
+    currency.explorer_urls(&network)
+}
+
+pub async fn get_receive_decimals(
+    client: &Client,
+    currency: String,
+    network: String,
+) -> Result<i32, Error> {
+    let currency = get_single_currency(client, currency).await?;
+    Ok(currency.network(&network)?.receiveDecimals)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::client::Client;
+    use crate::{
+        client::Client,
+        test_fixtures::{self, erc20_network_json, trc20_network_json},
+    };
     use std::env;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn currency() -> Currency {
+        serde_json::from_value(currency_json()).unwrap()
+    }
+
+    fn currency_json() -> Value {
+        test_fixtures::currency_json(
+            "USDT",
+            "Tether",
+            vec![erc20_network_json(), trc20_network_json()],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_currency_list_lenient() {
+        let mut broken = currency_json();
+        broken["currency"] = "BTC".into();
+        broken["networkList"][1]["receiveDecimals"] = "six".into();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/currencyList"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [currency_json(), broken, currency_json()]
+            })))
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        // The strict call loses the whole list.
+        assert!(get_currency_list(&client).await.is_err());
+
+        let (currencies, malformed) = get_currency_list_lenient(&client).await.unwrap();
+        assert_eq!(currencies.len(), 2);
+        assert!(currencies
+            .iter()
+            .all(|currency| currency.currency == "USDT"));
+        assert_eq!(malformed.len(), 1);
+        assert_eq!(malformed[0].0, 1);
+    }
+
+    #[test]
+    fn test_dedupe_currencies() {
+        let mut duplicate = currency_json();
+        duplicate["name"] = "Tether USD".into();
+        duplicate["sendStatusAll"] = false.into();
+        duplicate["networkList"][0]["network"] = "BSC".into();
+        duplicate["networkList"][1]["receiveStatus"] = false.into();
+        let mut other = currency_json();
+        other["currency"] = "USDC".into();
+
+        let currencies: Vec<Currency> =
+            serde_json::from_value(serde_json::json!([currency_json(), other, duplicate])).unwrap();
+        let currencies = dedupe_currencies(currencies);
+
+        assert_eq!(currencies.len(), 2);
+        let usdt = &currencies[0];
+        assert_eq!(usdt.currency, "USDT");
+        assert_eq!(usdt.name, "Tether USD");
+        assert!(!usdt.sendStatusAll);
+        let networks: Vec<&str> = usdt
+            .networkList
+            .iter()
+            .map(|network| network.network.as_str())
+            .collect();
+        assert_eq!(networks, vec!["ETH", "TRX", "BSC"]);
+        assert!(!usdt.network("TRX").unwrap().receiveStatus);
+        assert_eq!(currencies[1].currency, "USDC");
+    }
+
+    #[test]
+    fn test_estimated_processing_window() {
+        let mut network = currency().network("ETH").unwrap().clone();
+        assert_eq!(
+            network.estimated_processing_window(),
+            (Duration::from_secs(144), Duration::from_secs(768))
+        );
+
+        network.network = "BTC".to_string();
+        network.confirmationsMinimum = 2;
+        network.confirmationsMaximum = 6;
+        assert_eq!(
+            network.estimated_processing_window(),
+            (Duration::from_secs(1200), Duration::from_secs(3600))
+        );
+    }
+
+    #[test]
+    fn test_networks_by_priority() {
+        let mut json = currency_json();
+        let mut disabled = json["networkList"][1].clone();
+        disabled["network"] = "BSC".into();
+        disabled["sendStatus"] = false.into();
+        let networks = json["networkList"].as_array_mut().unwrap();
+        networks.insert(0, disabled);
+        networks.swap(1, 2);
+        let currency: Currency = serde_json::from_value(json).unwrap();
+
+        let order: Vec<&str> = currency
+            .networks_by_priority()
+            .iter()
+            .map(|network| network.network.as_str())
+            .collect();
+        assert_eq!(order, vec!["ETH", "TRX", "BSC"]);
+    }
+
+    #[test]
+    fn test_confirmation_eta() {
+        let mut network = currency().network("ETH").unwrap().clone();
+        network.network = "BTC".to_string();
+        network.confirmationsMinimum = 6;
+
+        assert_eq!(network.confirmation_eta(2), Duration::from_secs(2400));
+        assert_eq!(network.confirmation_eta(6), Duration::ZERO);
+        assert_eq!(network.confirmation_eta(9), Duration::ZERO);
+
+        // Networks missing from the block time table assume a block every minute.
+        network.network = "UNKNOWN".to_string();
+        assert_eq!(network.confirmation_eta(2), Duration::from_secs(240));
+    }
+
+    #[test]
+    fn test_is_fiat() {
+        let currency = |code: &str| -> Currency {
+            serde_json::from_value(test_fixtures::currency_json(code, code, vec![])).unwrap()
+        };
+
+        assert!(currency("USD").is_fiat());
+        assert!(!currency("BTC").is_fiat());
+        assert!(!currency("USDT").is_fiat());
+    }
 
     #[tokio::test]
     async fn test_get_currency_list() {
@@ -144,7 +507,7 @@ mod tests {
             println!("{:?}", currency);
         }
 
-        assert!(currency_list.len() > 0);
+        assert!(!currency_list.is_empty());
     }
 
     #[tokio::test]
@@ -156,4 +519,46 @@ mod tests {
         println!("{:?}", currency);
         assert_eq!(currency.currency, "BTC");
     }
+
+    #[tokio::test]
+    async fn test_get_receive_decimals() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/currencyList"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "data": [currency_json()] })),
+            )
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        let decimals = get_receive_decimals(&client, "USDT".to_string(), "trx".to_string())
+            .await
+            .unwrap();
+        assert_eq!(decimals, 6);
+
+        match get_receive_decimals(&client, "USDT".to_string(), "BSC".to_string()).await {
+            Err(Error::ApiError(error)) => assert_eq!(error.errorCode, 404),
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_explorer_urls() {
+        let urls = currency().explorer_urls("TRX").unwrap();
+
+        assert_eq!(urls.explorer, "https://tronscan.org");
+        assert_eq!(urls.explorer_hash, "https://tronscan.org/#/transaction/");
+        assert_eq!(urls.explorer_address, "https://tronscan.org/#/address/");
+        assert_eq!(
+            urls.explorer_contract,
+            Some("https://tronscan.org/#/token20/".to_string())
+        );
+
+        match currency().explorer_urls("BSC") {
+            Err(Error::ApiError(error)) => assert_eq!(error.errorCode, 404),
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+    }
 }
@@ -1,5 +1,8 @@
+pub mod consistency;
+pub mod diff;
 pub mod exchange_rate;
 pub mod info;
+pub mod network_selection;
 pub mod pair_info;
 pub mod pair_list;
 pub mod validate_address;
@@ -0,0 +1,230 @@
+use futures::future::join_all;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::{
+    amount::parse_amount,
+    client::Client,
+    currency::{
+        info::{get_single_currency, Currency, Network},
+        pair_info::get_pair_info,
+    },
+    Error,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/**
+   ### Policy for choosing a network when an order or rate call does not specify one.
+   Only networks that are enabled for the side being chosen are considered.
+   - `DefaultFlag`: The network flagged as default by the API
+   - `LowestFee`: The receive network with the lowest network fee, quoted through the pair information of every candidate.
+     Network fees are charged on the receive side, so send networks fall back to the default flag.
+   - `FewestConfirmations`: The network with the lowest `confirmationsMinimum`
+*/
+pub enum NetworkSelectionPolicy {
+    DefaultFlag,
+    LowestFee,
+    FewestConfirmations,
+}
+
+/**
+ * Fills in the send and receive networks that are not given, using the network selection policy of the client.
+ * Networks are returned unchanged if the client has no policy. A network stays `None` if no candidate is enabled,
+ * leaving the choice to the API.
+ */
+pub async fn resolve_networks(
+    client: &Client,
+    send: &str,
+    receive: &str,
+    send_network: Option<String>,
+    receive_network: Option<String>,
+) -> Result<(Option<String>, Option<String>), Error> {
+    let Some(policy) = client.get_network_selection_policy() else {
+        return Ok((send_network, receive_network));
+    };
+
+    let send_network = match send_network {
+        Some(network) => Some(network),
+        None => {
+            let currency = get_single_currency(client, send.to_string()).await?;
+            let candidates: Vec<&Network> = currency
+                .networkList
+                .iter()
+                .filter(|network| network.sendStatus)
+                .collect();
+            match policy {
+                NetworkSelectionPolicy::FewestConfirmations => fewest_confirmations(&candidates),
+                _ => default_flag(&candidates),
+            }
+        }
+    };
+
+    let receive_network = match receive_network {
+        Some(network) => Some(network),
+        None => {
+            let currency = get_single_currency(client, receive.to_string()).await?;
+            let candidates: Vec<&Network> = currency
+                .networkList
+                .iter()
+                .filter(|network| network.receiveStatus)
+                .collect();
+            match policy {
+                NetworkSelectionPolicy::DefaultFlag => default_flag(&candidates),
+                NetworkSelectionPolicy::FewestConfirmations => fewest_confirmations(&candidates),
+                NetworkSelectionPolicy::LowestFee => {
+                    lowest_fee(client, send, &currency, &candidates, send_network.clone()).await
+                }
+            }
+        }
+    };
+
+    Ok((send_network, receive_network))
+}
+
+fn default_flag(candidates: &[&Network]) -> Option<String> {
+    candidates
+        .iter()
+        .find(|network| network.isDefault)
+        .map(|network| network.network.clone())
+}
+
+fn fewest_confirmations(candidates: &[&Network]) -> Option<String> {
+    candidates
+        .iter()
+        .min_by_key(|network| network.confirmationsMinimum)
+        .map(|network| network.network.clone())
+}
+
+// Quotes every candidate concurrently. Candidates that cannot be quoted are skipped.
+async fn lowest_fee(
+    client: &Client,
+    send: &str,
+    receive: &Currency,
+    candidates: &[&Network],
+    send_network: Option<String>,
+) -> Option<String> {
+    let quotes = join_all(candidates.iter().map(|network| {
+        get_pair_info(
+            client,
+            send.to_string(),
+            receive.currency.clone(),
+            send_network.clone(),
+            Some(network.network.clone()),
+            None,
+        )
+    }))
+    .await;
+
+    let mut cheapest: Option<(Decimal, &Network)> = None;
+    for (network, quote) in candidates.iter().zip(quotes) {
+        let fee = match quote.and_then(|pair| parse_amount("networkFee", &pair.networkFee)) {
+            Ok(fee) => fee,
+            Err(error) => {
+                log::warn!("Skipping network {}: {}", network.network, error);
+                continue;
+            }
+        };
+        let cheaper = match cheapest {
+            Some((lowest, _)) => fee < lowest,
+            None => true,
+        };
+        if cheaper {
+            cheapest = Some((fee, network));
+        }
+    }
+    cheapest.map(|(_, network)| network.network.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{currency_json, network_json};
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn network(code: &str, is_default: bool, confirmations: i32) -> serde_json::Value {
+        let mut network = network_json(code, code);
+        network["isDefault"] = is_default.into();
+        network["receiveDecimals"] = 6.into();
+        network["confirmationsMinimum"] = confirmations.into();
+        network["confirmationsMaximum"] = confirmations.into();
+        network
+    }
+
+    fn pair(network_fee: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": {
+                "minimumAmount": "0.001",
+                "maximumAmount": "10",
+                "networkFee": network_fee,
+                "confirmations": 2,
+                "processingTime": "5-10 min"
+            }
+        }))
+    }
+
+    async fn server() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/currencyList"))
+            .and(query_param("currency", "USDT"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [currency_json(
+                    "USDT",
+                    "Tether",
+                    vec![network("ETH", true, 12), network("TRX", false, 20)]
+                )]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/pairInfo"))
+            .and(query_param("receiveNetwork", "ETH"))
+            .respond_with(pair("4.5"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/pairInfo"))
+            .and(query_param("receiveNetwork", "TRX"))
+            .respond_with(pair("1"))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_resolve_networks_default_flag() {
+        let server = server().await;
+        let client = Client::new(server.uri(), "key".to_string())
+            .with_network_selection_policy(NetworkSelectionPolicy::DefaultFlag);
+
+        let networks = resolve_networks(&client, "BTC", "USDT", Some("BTC".to_string()), None)
+            .await
+            .unwrap();
+        assert_eq!(networks, (Some("BTC".to_string()), Some("ETH".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_networks_lowest_fee() {
+        let server = server().await;
+        let client = Client::new(server.uri(), "key".to_string())
+            .with_network_selection_policy(NetworkSelectionPolicy::LowestFee);
+
+        let networks = resolve_networks(&client, "BTC", "USDT", Some("BTC".to_string()), None)
+            .await
+            .unwrap();
+        assert_eq!(networks, (Some("BTC".to_string()), Some("TRX".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_networks_without_policy() {
+        let client = Client::new("http://localhost".to_string(), "key".to_string());
+
+        let networks = resolve_networks(&client, "BTC", "USDT", None, None)
+            .await
+            .unwrap();
+        assert_eq!(networks, (None, None));
+    }
+}
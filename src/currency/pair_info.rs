@@ -1,9 +1,10 @@
-use crate::{client::Client, EasyBit, Error};
-use reqwest::StatusCode;
-use serde::Deserialize;
-use serde_json::Value;
+use std::{str::FromStr, time::Duration};
 
-#[derive(Deserialize, Debug)]
+use crate::{amount::parse_amount, client::Client, response::handle_data_response, Error};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
 #[allow(non_snake_case)]
 /**
     ### Pair information.
@@ -22,6 +23,122 @@ pub struct Pair {
     pub processingTime: String,
 }
 
+// Parses the upper bound of a processing time such as "5-30 minutes", "1 hour" or "1.5 hours".
+fn parse_processing_time(processing_time: &str) -> Option<Duration> {
+    let is_number = |c: char| c.is_ascii_digit() || c == '.' || c == ',';
+
+    let text = processing_time.trim().to_lowercase();
+    let end = text.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let start = text[..end]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !is_number(*c))
+        .map_or(0, |(index, c)| index + c.len_utf8());
+    // A decimal comma is read as a decimal point.
+    let upper = Decimal::from_str(&text[start..end].replace(',', ".")).ok()?;
+
+    let unit: String = text[end..]
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_alphabetic())
+        .collect();
+    let seconds = match unit.as_str() {
+        "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3600,
+        "d" | "day" | "days" => 86400,
+        _ => return None,
+    };
+    let seconds = upper
+        .checked_mul(Decimal::from(seconds))?
+        .round()
+        .to_u64()?;
+    Some(Duration::from_secs(seconds))
+}
+
+impl Pair {
+    /**
+     * Returns `minimumAmount` as an exact decimal.
+     */
+    pub fn minimum_amount_decimal(&self) -> Result<Decimal, Error> {
+        parse_amount("minimumAmount", &self.minimumAmount)
+    }
+
+    /**
+     * Returns `maximumAmount` as an exact decimal.
+     */
+    pub fn maximum_amount_decimal(&self) -> Result<Decimal, Error> {
+        parse_amount("maximumAmount", &self.maximumAmount)
+    }
+
+    /**
+     * Returns `networkFee` as an exact decimal.
+     */
+    pub fn network_fee_decimal(&self) -> Result<Decimal, Error> {
+        parse_amount("networkFee", &self.networkFee)
+    }
+
+    /**
+     * Returns the upper bound of `processingTime`, e.g. 30 minutes for "5-30 minutes".
+     * Returns `None` if the processing time cannot be parsed.
+     */
+    pub fn max_processing_time(&self) -> Option<Duration> {
+        parse_processing_time(&self.processingTime)
+    }
+
+    /**
+     * Splits `total` into amounts that each lie within `[minimumAmount, maximumAmount]`.
+     * Amounts are as large as possible, with the last ones reduced so that no amount falls below the minimum.
+     * Returns `Error::InvalidAmount` if `total` is not positive or cannot be split this way.
+     */
+    pub fn split_amount(&self, total: Decimal) -> Result<Vec<Decimal>, Error> {
+        let minimum = self.minimum_amount_decimal()?;
+        let maximum = self.maximum_amount_decimal()?;
+        if maximum <= Decimal::ZERO || minimum > maximum {
+            return Err(Error::InvalidAmount(format!(
+                "cannot split with minimum {} and maximum {}",
+                minimum, maximum
+            )));
+        }
+        if total <= Decimal::ZERO {
+            return Err(Error::InvalidAmount(format!(
+                "cannot split {}, the total must be positive",
+                total
+            )));
+        }
+        if total < minimum {
+            return Err(Error::InvalidAmount(format!(
+                "{} is below the minimum amount {}",
+                total, minimum
+            )));
+        }
+
+        // The fewest number of orders that stay under the maximum.
+        let count = (total / maximum).ceil();
+        if minimum * count > total {
+            return Err(Error::InvalidAmount(format!(
+                "{} cannot be split into {} orders of at least {}",
+                total, count, minimum
+            )));
+        }
+
+        // Fill all but the last order to the maximum, then move from the full orders into the
+        // last one until it reaches the minimum.
+        let mut chunks = vec![maximum; count.to_usize().unwrap_or(1).saturating_sub(1)];
+        let mut last = total - maximum * Decimal::from(chunks.len());
+        for chunk in chunks.iter_mut().rev() {
+            if last >= minimum {
+                break;
+            }
+            let moved = (minimum - last).min(*chunk - minimum);
+            *chunk -= moved;
+            last += moved;
+        }
+        chunks.push(last);
+        Ok(chunks)
+    }
+}
+
 #[allow(non_snake_case)]
 pub async fn get_pair_info(
     client: &Client,
@@ -35,40 +152,16 @@ pub async fn get_pair_info(
     let path = "/pairInfo";
 
     // Make the request and set API key.
-    let response = reqwest::Client::new()
-        .get(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key())
-        .query(&[
-            ("send", send),
-            ("receive", receive),
-            ("sendNetwork", sendNetwork.unwrap_or_default()),
-            ("receiveNetwork", receiveNetwork.unwrap_or_default()),
-            ("amountType", amountType.unwrap_or_default()),
-        ])
-        .send()
-        .await?;
-
-    match response.status() {
-        StatusCode::OK => {
-            let json: Value = response.json().await?;
-            match json.get("data") {
-                Some(data) => {
-                    let pair: Pair = serde_json::from_value(data.clone())?;
-                    Ok(pair)
-                }
-                None => {
-                    let error: EasyBit = serde_json::from_value(json)?;
-                    log::error!("{:?}", error);
-                    Err(Error::ApiError(error))
-                }
-            }
-        }
-        _ => {
-            let error: EasyBit = response.json().await?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
-        }
-    }
+    let request = client.get(path)?.query(&[
+        ("send", send),
+        ("receive", receive),
+        ("sendNetwork", sendNetwork.unwrap_or_default()),
+        ("receiveNetwork", receiveNetwork.unwrap_or_default()),
+        ("amountType", amountType.unwrap_or_default()),
+    ]);
+    let response = client.send(request).await?;
+
+    handle_data_response(response).await
 }
 
 #[cfg(test)]
@@ -77,6 +170,93 @@ mod tests {
     use crate::client::Client;
     use std::env;
 
+    fn pair(minimum: &str, maximum: &str) -> Pair {
+        Pair {
+            minimumAmount: minimum.to_string(),
+            maximumAmount: maximum.to_string(),
+            networkFee: "0".to_string(),
+            confirmations: 2,
+            processingTime: "5-10 min".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_processing_time() {
+        assert_eq!(
+            parse_processing_time("5-30 minutes"),
+            Some(Duration::from_secs(1800))
+        );
+        assert_eq!(
+            parse_processing_time("5 - 10 min"),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(
+            parse_processing_time("1-2 Hours"),
+            Some(Duration::from_secs(7200))
+        );
+        assert_eq!(parse_processing_time("45s"), Some(Duration::from_secs(45)));
+        assert_eq!(
+            parse_processing_time("5–30 minutes"),
+            Some(Duration::from_secs(1800))
+        );
+
+        // Fractional upper bounds, with a decimal point or comma.
+        assert_eq!(
+            parse_processing_time("1.5 hours"),
+            Some(Duration::from_secs(5400))
+        );
+        assert_eq!(
+            parse_processing_time("1-2,5 hours"),
+            Some(Duration::from_secs(9000))
+        );
+
+        assert_eq!(parse_processing_time("instant"), None);
+        assert_eq!(parse_processing_time("30"), None);
+        assert_eq!(parse_processing_time("1.2.3 hours"), None);
+    }
+
+    #[test]
+    fn test_split_amount() {
+        let pair = pair("0.01", "1");
+
+        assert_eq!(
+            pair.split_amount(Decimal::new(15, 1)).unwrap(),
+            vec![Decimal::ONE, Decimal::new(5, 1)]
+        );
+
+        // The remainder is below the minimum, so it is topped up from the first order.
+        assert_eq!(
+            pair.split_amount(Decimal::new(1005, 3)).unwrap(),
+            vec![Decimal::new(995, 3), Decimal::new(1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_split_amount_impossible() {
+        // Two orders are needed, but two minimum orders already exceed the total.
+        match pair("0.8", "1").split_amount(Decimal::new(15, 1)) {
+            Err(Error::InvalidAmount(_)) => (),
+            other => panic!("Expected an invalid amount error, got {:?}", other),
+        }
+
+        assert!(pair("0.01", "1").split_amount(Decimal::new(5, 3)).is_err());
+    }
+
+    #[test]
+    fn test_split_amount_zero() {
+        // A zero minimum lets a zero total pass the minimum check, it must not reach the split.
+        match pair("0", "1").split_amount(Decimal::ZERO) {
+            Err(Error::InvalidAmount(_)) => (),
+            other => panic!("Expected an invalid amount error, got {:?}", other),
+        }
+        assert!(pair("0", "1").split_amount(Decimal::new(-1, 0)).is_err());
+
+        assert_eq!(
+            pair("0", "1").split_amount(Decimal::new(15, 1)).unwrap(),
+            vec![Decimal::ONE, Decimal::new(5, 1)]
+        );
+    }
+
     #[tokio::test]
     async fn test_get_pair_info() {
         let client = Client::new(env::var("URL").unwrap(), env::var("API_KEY").unwrap());
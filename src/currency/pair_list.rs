@@ -1,41 +1,193 @@
-use reqwest::StatusCode;
-use serde_json::Value;
+use std::{collections::HashSet, fmt, str::FromStr};
 
-use crate::{client::Client, EasyBit, Error};
+use crate::{client::Client, response::handle_data_response, Error};
 
-pub async fn get_pair_list(client: &Client) -> Result<Vec<String>, Error> {
-    // Define the URL.
-    let path = "/pairList";
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/**
+   ### Trading pair.
+   Parsed from an entry of the pair list in the form `sendCurrency_sendNetwork_receiveCurrency_receiveNetwork`.
+   - `send`: Currency code for the currency to send
+   - `send_network`: Network code for the network to send on
+   - `receive`: Currency code for the currency to receive
+   - `receive_network`: Network code for the network to receive on
+*/
+pub struct TradingPair {
+    pub send: String,
+    pub send_network: String,
+    pub receive: String,
+    pub receive_network: String,
+}
 
-    // Make the request and set API key.
-    let response = reqwest::Client::new()
-        .get(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key())
-        .send()
-        .await?;
-
-    match response.status() {
-        StatusCode::OK => {
-            // Convert the response to a Vec<String>
-            let json: Value = response.json().await?;
-            match json.get("data") {
-                Some(data) => {
-                    let pair_list: Vec<String> = serde_json::from_value(data.clone())?;
-                    Ok(pair_list)
-                }
-                None => {
-                    let error: EasyBit = serde_json::from_value(json)?;
-                    log::error!("{:?}", error);
-                    Err(Error::ApiError(error))
-                }
+impl FromStr for TradingPair {
+    type Err = Error;
+
+    fn from_str(pair: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = pair.split('_').collect();
+        match parts.as_slice() {
+            [send, send_network, receive, receive_network]
+                if parts.iter().all(|part| !part.is_empty()) =>
+            {
+                Ok(TradingPair {
+                    send: send.to_string(),
+                    send_network: send_network.to_string(),
+                    receive: receive.to_string(),
+                    receive_network: receive_network.to_string(),
+                })
             }
+            _ => Err(Error::InvalidPair(format!(
+                "expected sendCurrency_sendNetwork_receiveCurrency_receiveNetwork, got {:?}",
+                pair
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for TradingPair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}_{}_{}_{}",
+            self.send, self.send_network, self.receive, self.receive_network
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/**
+   ### Index of supported trading pairs.
+   Answers whether a pair is supported in constant time. Currency and network codes are compared without regard to case.
+*/
+pub struct PairIndex {
+    pairs: HashSet<TradingPair>,
+}
+
+impl PairIndex {
+    /**
+     * Builds an index from trading pairs.
+     */
+    pub fn new(pairs: impl IntoIterator<Item = TradingPair>) -> PairIndex {
+        PairIndex {
+            pairs: pairs.into_iter().map(|pair| normalize(&pair)).collect(),
         }
-        _ => {
-            let error: EasyBit = response.json().await?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
+    }
+
+    /**
+     * Returns true if sending `send` on `send_network` for `receive` on `receive_network` is supported.
+     */
+    pub fn contains(
+        &self,
+        send: &str,
+        send_network: &str,
+        receive: &str,
+        receive_network: &str,
+    ) -> bool {
+        self.contains_pair(&TradingPair {
+            send: send.to_string(),
+            send_network: send_network.to_string(),
+            receive: receive.to_string(),
+            receive_network: receive_network.to_string(),
+        })
+    }
+
+    /**
+     * Returns true if the trading pair is supported.
+     */
+    pub fn contains_pair(&self, pair: &TradingPair) -> bool {
+        self.pairs.contains(&normalize(pair))
+    }
+
+    /**
+     * Returns the number of supported pairs.
+     */
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /**
+     * Returns true if no pairs are supported.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+// Uppercases the codes of a pair, as used by the API.
+fn normalize(pair: &TradingPair) -> TradingPair {
+    TradingPair {
+        send: pair.send.trim().to_uppercase(),
+        send_network: pair.send_network.trim().to_uppercase(),
+        receive: pair.receive.trim().to_uppercase(),
+        receive_network: pair.receive_network.trim().to_uppercase(),
+    }
+}
+
+/**
+ * Parses the entries of a pair list, keeping the malformed entries apart with their error
+ * so that one bad entry does not lose the whole list.
+ */
+pub fn parse_pair_list(pair_list: Vec<String>) -> (Vec<TradingPair>, Vec<(String, Error)>) {
+    let mut pairs = Vec::new();
+    let mut malformed = Vec::new();
+    for entry in pair_list {
+        match entry.parse::<TradingPair>() {
+            Ok(pair) => pairs.push(pair),
+            Err(error) => malformed.push((entry, error)),
         }
     }
+    (pairs, malformed)
+}
+
+pub async fn get_pair_list_parsed(
+    client: &Client,
+) -> Result<(Vec<TradingPair>, Vec<(String, Error)>), Error> {
+    let pair_list = get_pair_list(client).await?;
+    Ok(parse_pair_list(pair_list))
+}
+
+/**
+ * Returns the distinct pairs of the pair list that send or receive on `network`, in the order of the list.
+ * Malformed entries are logged and skipped.
+ */
+pub async fn get_pairs_on_network(
+    client: &Client,
+    network: &str,
+) -> Result<Vec<TradingPair>, Error> {
+    let (pairs, malformed) = get_pair_list_parsed(client).await?;
+    for (entry, error) in malformed {
+        log::warn!("Skipping pair list entry {:?}: {}", entry, error);
+    }
+
+    let network = network.trim();
+    let mut seen = HashSet::new();
+    Ok(pairs
+        .into_iter()
+        .filter(|pair| {
+            pair.send_network.eq_ignore_ascii_case(network)
+                || pair.receive_network.eq_ignore_ascii_case(network)
+        })
+        .filter(|pair| seen.insert(pair.clone()))
+        .collect())
+}
+
+/**
+ * Fetches the pair list once and indexes it. Malformed entries are logged and skipped.
+ */
+pub async fn build_pair_index(client: &Client) -> Result<PairIndex, Error> {
+    let (pairs, malformed) = get_pair_list_parsed(client).await?;
+    for (entry, error) in malformed {
+        log::warn!("Skipping pair list entry {:?}: {}", entry, error);
+    }
+    Ok(PairIndex::new(pairs))
+}
+
+pub async fn get_pair_list(client: &Client) -> Result<Vec<String>, Error> {
+    // Define the URL.
+    let path = "/pairList";
+
+    // Make the request and set API key.
+    let response = client.send(client.get(path)?).await?;
+
+    handle_data_response(response).await
 }
 
 #[cfg(test)]
@@ -43,6 +195,98 @@ mod tests {
     use super::*;
     use crate::client::Client;
     use std::env;
+    use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_parse_pair_list() {
+        let (pairs, malformed) = parse_pair_list(vec![
+            "BTC_BTC_ETH_ETH".to_string(),
+            "BTC_BTC_ETH".to_string(),
+            "USDT_TRX_BTC_BTC".to_string(),
+            "BTC__ETH_ETH".to_string(),
+        ]);
+
+        assert_eq!(
+            pairs,
+            vec![
+                TradingPair {
+                    send: "BTC".to_string(),
+                    send_network: "BTC".to_string(),
+                    receive: "ETH".to_string(),
+                    receive_network: "ETH".to_string(),
+                },
+                TradingPair {
+                    send: "USDT".to_string(),
+                    send_network: "TRX".to_string(),
+                    receive: "BTC".to_string(),
+                    receive_network: "BTC".to_string(),
+                },
+            ]
+        );
+        assert_eq!(pairs[1].to_string(), "USDT_TRX_BTC_BTC");
+
+        let malformed: Vec<&str> = malformed
+            .iter()
+            .map(|(entry, error)| {
+                assert!(matches!(error, Error::InvalidPair(_)));
+                entry.as_str()
+            })
+            .collect();
+        assert_eq!(malformed, vec!["BTC_BTC_ETH", "BTC__ETH_ETH"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_pairs_on_network() {
+        let server = MockServer::start().await;
+        Mock::given(path("/pairList"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [
+                    "BTC_BTC_ETH_ETH",
+                    "USDT_TRX_BTC_BTC",
+                    "USDT_ETH_USDT_TRX",
+                    "USDT_TRX_BTC_BTC",
+                    "TRX_TRX",
+                    "ETH_ETH_USDT_BSC",
+                ]
+            })))
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        let pairs: Vec<String> = get_pairs_on_network(&client, "trx")
+            .await
+            .unwrap()
+            .iter()
+            .map(TradingPair::to_string)
+            .collect();
+        assert_eq!(pairs, vec!["USDT_TRX_BTC_BTC", "USDT_ETH_USDT_TRX"]);
+
+        assert!(get_pairs_on_network(&client, "SOL")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_build_pair_index() {
+        let server = MockServer::start().await;
+        Mock::given(path("/pairList"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": ["BTC_BTC_ETH_ETH", "USDT_TRX_BTC_BTC", "BTC_BTC"]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        let index = build_pair_index(&client).await.unwrap();
+        assert_eq!(index.len(), 2);
+        assert!(index.contains("BTC", "BTC", "ETH", "ETH"));
+        assert!(index.contains("usdt", "trx", "btc", "btc"));
+        assert!(!index.contains("ETH", "ETH", "BTC", "BTC"));
+        assert!(!index.contains("USDT", "ETH", "BTC", "BTC"));
+        assert!(index.contains_pair(&"USDT_TRX_BTC_BTC".parse().unwrap()));
+    }
 
     #[tokio::test]
     async fn test_get_pair_list() {
@@ -54,6 +298,6 @@ mod tests {
             println!("{:?}", pair);
         }
 
-        assert!(pair_list.len() > 0);
+        assert!(!pair_list.is_empty());
     }
 }
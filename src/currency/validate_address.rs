@@ -1,22 +1,139 @@
-use reqwest::StatusCode;
+use std::fmt;
 
-use crate::{client::Client, EasyBit, Error};
+use reqwest::{Response, StatusCode};
+use tiny_keccak::{Hasher, Keccak};
 
-pub async fn validate_address(
+use crate::{
+    client::Client,
+    response::{api_error, read_api_error},
+    Error,
+};
+
+// Network codes of chains that use EVM style addresses.
+const EVM_NETWORKS: [&str; 9] = [
+    "ETH", "BSC", "MATIC", "POLYGON", "ARBITRUM", "OPTIMISM", "AVAXC", "BASE", "FTM",
+];
+
+// Length bounds of addresses on any supported network, from short account names to long Cardano addresses.
+const MIN_ADDRESS_LENGTH: usize = 3;
+const MAX_ADDRESS_LENGTH: usize = 256;
+
+// EasyBit error codes of an address that failed validation.
+const INVALID_ADDRESS_ERROR_CODES: [i32; 1] = [1018];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+   ### Address format of a family of networks.
+   - `Evm`: `0x` followed by 40 hexadecimal characters, used by Ethereum and EVM compatible networks
+   - `Bitcoin`: Legacy (`1`), script (`3`) or segwit (`bc1`) Bitcoin addresses
+   - `Tron`: Base58 addresses starting with `T`
+*/
+pub enum AddressFormat {
+    Evm,
+    Bitcoin,
+    Tron,
+}
+
+impl AddressFormat {
+    /**
+     * Returns the address format expected on a network, or `None` if the network is not known to this library.
+     */
+    pub fn for_network(network: &str) -> Option<AddressFormat> {
+        let network = network.to_uppercase();
+        if EVM_NETWORKS.contains(&network.as_str()) {
+            Some(AddressFormat::Evm)
+        } else if network == "BTC" {
+            Some(AddressFormat::Bitcoin)
+        } else if network == "TRX" {
+            Some(AddressFormat::Tron)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Detects the address format from the shape of an address, without checking its checksum.
+     */
+    pub fn detect(address: &str) -> Option<AddressFormat> {
+        let address = address.trim();
+        if address.starts_with("0x") && address.len() == 42 {
+            Some(AddressFormat::Evm)
+        } else if address.starts_with('T') && address.len() == 34 {
+            Some(AddressFormat::Tron)
+        } else if address.to_lowercase().starts_with("bc1")
+            || ((address.starts_with('1') || address.starts_with('3'))
+                && (25..=34).contains(&address.len()))
+        {
+            Some(AddressFormat::Bitcoin)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for AddressFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressFormat::Evm => write!(f, "EVM"),
+            AddressFormat::Bitcoin => write!(f, "Bitcoin"),
+            AddressFormat::Tron => write!(f, "Tron"),
+        }
+    }
+}
+
+/**
+ * Checks that an address has the format expected on a network.
+ * Returns `Error::AddressNetworkMismatch` if the address clearly belongs to another family of networks.
+ * Networks and addresses whose format is not known to this library are accepted.
+ */
+pub fn check_address_network(network: &str, address: &str) -> Result<(), Error> {
+    match (
+        AddressFormat::for_network(network),
+        AddressFormat::detect(address),
+    ) {
+        (Some(expected), Some(found)) if expected != found => Err(Error::AddressNetworkMismatch {
+            network: network.to_uppercase(),
+            expected,
+            found,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/**
+ * Checks that an address is plausible on any network: not empty, within a sane length and free of whitespace and
+ * control characters. Returns `Error::InvalidAddress` otherwise. Does not check the format of a specific network.
+ */
+pub fn check_address_sanity(address: &str) -> Result<(), Error> {
+    if address.trim().is_empty() {
+        return Err(invalid("address is empty"));
+    }
+    if address.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(invalid("address contains whitespace or control characters"));
+    }
+    if !(MIN_ADDRESS_LENGTH..=MAX_ADDRESS_LENGTH).contains(&address.chars().count()) {
+        return Err(Error::InvalidAddress(format!(
+            "address must be {} to {} characters long",
+            MIN_ADDRESS_LENGTH, MAX_ADDRESS_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+// Sends an address validation request.
+async fn validation_request(
     client: &Client,
     currency: String,
     address: String,
     network: Option<String>,
     tag: Option<String>,
-) -> Result<(), Error> {
+) -> Result<Response, Error> {
     // Define the path.
     let path = "/validateAddress";
 
     log::info!("{:?}", format!("{}{}", client.get_url(), path));
     // Make the GET request and set API key. The query should only contain items that are not None.
-    let request = reqwest::Client::new()
-        .get(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key());
+    let request = client.get(path)?;
 
     // Even if the network and tag parameters are empty, the API complains.
     // So we must only include them if they are Some.
@@ -33,16 +150,194 @@ pub async fn validate_address(
         query_tuple_array.push(("tag", tag));
     }
 
-    let response = request.query(&query_tuple_array).send().await?;
+    client.send(request.query(&query_tuple_array)).await
+}
+
+pub async fn validate_address(
+    client: &Client,
+    currency: String,
+    address: String,
+    network: Option<String>,
+    tag: Option<String>,
+) -> Result<(), Error> {
+    let response = validation_request(client, currency, address, network, tag).await?;
 
     match response.status() {
         StatusCode::OK => Ok(()),
-        _ => {
-            let error: EasyBit = response.json().await?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
+        _ => Err(api_error(response).await),
+    }
+}
+
+/**
+ * Validates an address with the API, returning `Ok(false)` if the API rejects the address.
+ * A rejection is an EasyBit error with the invalid address error code. Other API errors, such as an unknown currency
+ * or network, an invalid API key or rate limiting, are returned as `Error::ApiError`, and responses without an
+ * EasyBit error body as the error they cause.
+ */
+pub async fn validate_address_checked(
+    client: &Client,
+    currency: String,
+    address: String,
+    network: Option<String>,
+    tag: Option<String>,
+) -> Result<bool, Error> {
+    let response = validation_request(client, currency, address, network, tag).await?;
+
+    if response.status() == StatusCode::OK {
+        return Ok(true);
+    }
+    let error = read_api_error(response).await?;
+    if INVALID_ADDRESS_ERROR_CODES.contains(&error.errorCode) {
+        log::info!("Address rejected: {}", error);
+        return Ok(false);
+    }
+    log::error!("{:?}", error);
+    Err(Error::ApiError(error))
+}
+
+/**
+ * Checks the format and checksum of an address without contacting the API.
+ * The network code decides the address format, falling back to the currency code if no network is given.
+ * Supported formats are EVM addresses (EIP-55 checksum), Bitcoin (base58check and bech32) and Tron (base58check).
+ * An address in the format of another network is reported as `Error::AddressNetworkMismatch`.
+ * Addresses on other networks are accepted and left for the server to validate.
+ */
+pub fn validate_address_offline(
+    currency: &str,
+    network: Option<&str>,
+    address: &str,
+) -> Result<(), Error> {
+    let network = network.unwrap_or(currency);
+    check_address_network(network, address)?;
+
+    match AddressFormat::for_network(network) {
+        Some(AddressFormat::Evm) => validate_evm_address(address),
+        Some(AddressFormat::Bitcoin) => validate_bitcoin_address(address),
+        Some(AddressFormat::Tron) => validate_base58check(address, 0x41),
+        None => Ok(()),
+    }
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidAddress(reason.to_string())
+}
+
+fn validate_evm_address(address: &str) -> Result<(), Error> {
+    let hex = address
+        .strip_prefix("0x")
+        .ok_or_else(|| invalid("EVM address must start with 0x"))?;
+
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid(
+            "EVM address must contain 40 hexadecimal characters",
+        ));
+    }
+
+    // Addresses in a single case carry no checksum.
+    if hex == hex.to_lowercase() || hex == hex.to_uppercase() {
+        return Ok(());
+    }
+
+    // EIP-55: a letter is uppercase if the matching nibble of the keccak hash of the lowercase address is 8 or higher.
+    let mut hash = [0u8; 32];
+    let mut keccak = Keccak::v256();
+    keccak.update(hex.to_lowercase().as_bytes());
+    keccak.finalize(&mut hash);
+
+    for (i, c) in hex.chars().enumerate() {
+        let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+        if c.is_ascii_alphabetic() && c.is_ascii_uppercase() != (nibble >= 8) {
+            return Err(invalid("EVM address has an invalid EIP-55 checksum"));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_bitcoin_address(address: &str) -> Result<(), Error> {
+    if address.starts_with('1') {
+        validate_base58check(address, 0x00)
+    } else if address.starts_with('3') {
+        validate_base58check(address, 0x05)
+    } else if address.contains('1') {
+        // Segwit addresses of mainnet, as opposed to e.g. testnet (`tb`).
+        validate_bech32(address, "bc")
+    } else {
+        Err(invalid("Bitcoin address must start with 1, 3 or bc1"))
+    }
+}
+
+fn validate_base58check(address: &str, version: u8) -> Result<(), Error> {
+    let decoded = bs58::decode(address)
+        .with_check(None)
+        .into_vec()
+        .map_err(|_| invalid("address has an invalid base58 checksum"))?;
+
+    if decoded.len() != 21 || decoded[0] != version {
+        return Err(invalid("address has an unexpected length or version byte"));
+    }
+
+    Ok(())
+}
+
+fn validate_bech32(address: &str, expected_hrp: &str) -> Result<(), Error> {
+    const CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+    if address != address.to_lowercase() && address != address.to_uppercase() {
+        return Err(invalid("bech32 address must not mix upper and lower case"));
+    }
+    if address.len() < 14 || address.len() > 74 {
+        return Err(invalid("bech32 address has an invalid length"));
+    }
+
+    let address = address.to_lowercase();
+    let (hrp, data) = address.split_at(address.rfind('1').unwrap_or(0));
+    if hrp != expected_hrp {
+        return Err(invalid(&format!(
+            "bech32 address has human readable part {:?}, expected {:?}",
+            hrp, expected_hrp
+        )));
+    }
+    let data = data
+        .chars()
+        .skip(1)
+        .map(|c| CHARSET.find(c).map(|value| value as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| invalid("bech32 address contains invalid characters"))?;
+
+    if data.len() < 7 {
+        return Err(invalid("bech32 address is too short"));
+    }
+
+    // Checksum over the expanded human readable part followed by the data part.
+    let mut values: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    values.push(0);
+    values.extend(hrp.bytes().map(|b| b & 0x1f));
+    values.extend(&data);
+
+    // Witness version 0 uses bech32, later versions use bech32m.
+    let expected = if data[0] == 0 { 1 } else { 0x2bc830a3 };
+    if bech32_polymod(&values) != expected {
+        return Err(invalid("bech32 address has an invalid checksum"));
+    }
+
+    Ok(())
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+    let mut checksum: u32 = 1;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ffffff) << 5) ^ *value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
         }
     }
+    checksum
 }
 
 #[cfg(test)]
@@ -50,6 +345,76 @@ mod tests {
     use super::*;
     use crate::client::Client;
     use std::env;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_validate_address_checked() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/validateAddress"))
+            .and(query_param("address", "valid"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "success": 1,
+                "data": {}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/validateAddress"))
+            .and(query_param("address", "invalid"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "success": 0,
+                "errorCode": 1018,
+                "errorMessage": "Invalid address"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/validateAddress"))
+            .and(query_param("address", "unknown-network"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "success": 0,
+                "errorCode": 1004,
+                "errorMessage": "Network not found"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/validateAddress"))
+            .and(query_param("address", "unauthorized"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "success": 0,
+                "errorCode": 401,
+                "errorMessage": "Invalid API key"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/validateAddress"))
+            .and(query_param("address", "down"))
+            .respond_with(ResponseTemplate::new(502).set_body_string("Bad Gateway"))
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+        let validate = |address: &str| {
+            validate_address_checked(&client, "BTC".to_string(), address.to_string(), None, None)
+        };
+
+        assert!(validate("valid").await.unwrap());
+        assert!(!validate("invalid").await.unwrap());
+        match validate("unknown-network").await {
+            Err(Error::ApiError(error)) => assert_eq!(error.errorCode, 1004),
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+        assert!(matches!(
+            validate("unauthorized").await,
+            Err(Error::ApiError(_))
+        ));
+        assert!(validate("down").await.is_err());
+    }
 
     #[tokio::test]
     async fn test_validate_address() {
@@ -61,10 +426,124 @@ mod tests {
             Some("BTC".to_string()),
             None,
         )
-        .await
-        .unwrap();
+        .await;
         log::info!("{:?}", result);
 
-        assert!(result == ());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_check_address_sanity() {
+        assert!(check_address_sanity("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_ok());
+        assert!(check_address_sanity("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq").is_ok());
+
+        for address in [
+            "",
+            "   ",
+            "\t\n",
+            " 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "1A1zP1eP5QGefi2DMPTf TL5SLmv7DivfNa",
+            "0x",
+            &"a".repeat(257),
+        ] {
+            assert!(
+                matches!(check_address_sanity(address), Err(Error::InvalidAddress(_))),
+                "{:?} should be rejected",
+                address
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_evm_address_offline() {
+        assert!(validate_address_offline(
+            "ETH",
+            Some("ETH"),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        )
+        .is_ok());
+        assert!(validate_address_offline(
+            "USDT",
+            Some("BSC"),
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        )
+        .is_ok());
+
+        match validate_address_offline(
+            "ETH",
+            Some("ETH"),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD",
+        ) {
+            Err(Error::InvalidAddress(_)) => (),
+            other => panic!("Expected an invalid address error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_bitcoin_address_offline() {
+        assert!(
+            validate_address_offline("BTC", None, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa").is_ok()
+        );
+        assert!(validate_address_offline(
+            "BTC",
+            Some("BTC"),
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"
+        )
+        .is_ok());
+
+        match validate_address_offline("BTC", None, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb") {
+            Err(Error::InvalidAddress(_)) => (),
+            other => panic!("Expected an invalid address error, got {:?}", other),
+        }
+        match validate_address_offline(
+            "BTC",
+            Some("BTC"),
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdr",
+        ) {
+            Err(Error::InvalidAddress(_)) => (),
+            other => panic!("Expected an invalid address error, got {:?}", other),
+        }
+
+        // A valid testnet address is not a valid address on BTC.
+        match validate_address_offline(
+            "BTC",
+            Some("BTC"),
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+        ) {
+            Err(Error::InvalidAddress(reason)) => assert!(reason.contains("\"tb\""), "{}", reason),
+            other => panic!("Expected an invalid address error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_address_network() {
+        match validate_address_offline(
+            "BTC",
+            Some("BTC"),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        ) {
+            Err(Error::AddressNetworkMismatch {
+                network,
+                expected,
+                found,
+            }) => {
+                assert_eq!(network, "BTC");
+                assert_eq!(expected, AddressFormat::Bitcoin);
+                assert_eq!(found, AddressFormat::Evm);
+            }
+            other => panic!("Expected an address network mismatch, got {:?}", other),
+        }
+
+        assert!(check_address_network("BTC", "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq").is_ok());
+        assert!(check_address_network("bsc", "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_ok());
+        assert!(matches!(
+            check_address_network("ETH", "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t"),
+            Err(Error::AddressNetworkMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_unknown_network_offline() {
+        assert!(validate_address_offline("XMR", None, "anything").is_ok());
     }
 }
@@ -1,2 +1,3 @@
 pub mod refund;
+pub mod requirements;
 pub mod update;
@@ -0,0 +1,93 @@
+use crate::{
+    client::Client,
+    orders::status::{order_status, OrderStatus, Status, ValidationStatus},
+    Error,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/**
+   ### KYC requirements of an order.
+   The API does not report which documents an order needs, so the requirements are derived from the order status and validation status.
+    - `action_required`: The order status is "Action Request" and the customer must act before the order continues.
+    - `validation_status`: Current validation status of the order, `None` if no validation has been requested.
+    - `can_submit`: KYC documents can be submitted with `update_order_kyc`. Requires "Action Request" and a validation status of null, "awaiting" or "failed_allow_retry".
+    - `can_refund`: The order can be refunded with `refund_order`. Requires "Action Request" and a validation status of null, "awaiting", "failed_allow_retry" or "failed_deny_retry".
+*/
+pub struct KycRequirements {
+    pub action_required: bool,
+    pub validation_status: Option<ValidationStatus>,
+    pub can_submit: bool,
+    pub can_refund: bool,
+}
+
+impl KycRequirements {
+    /**
+     * Derives the KYC requirements from an order status.
+     */
+    pub fn from_status(status: &Status) -> KycRequirements {
+        let action_required = status.status_enum() == OrderStatus::ActionRequest;
+        let validation_status = status.validation_status_enum();
+
+        let can_submit = action_required
+            && matches!(
+                validation_status,
+                None | Some(ValidationStatus::Awaiting) | Some(ValidationStatus::FailedAllowRetry)
+            );
+        let can_refund = can_submit
+            || (action_required && validation_status == Some(ValidationStatus::FailedDenyRetry));
+
+        KycRequirements {
+            action_required,
+            validation_status,
+            can_submit,
+            can_refund,
+        }
+    }
+}
+
+pub async fn get_kyc_requirements(client: &Client, id: String) -> Result<KycRequirements, Error> {
+    let status = order_status(client, id).await?;
+    Ok(KycRequirements::from_status(&status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(status: &str, validation_status: Option<&str>) -> Status {
+        serde_json::from_value(serde_json::json!({
+            "id": "test",
+            "status": status,
+            "receiveAmount": "1.9",
+            "hashIn": null,
+            "hashOut": null,
+            "validationStatus": validation_status,
+            "createdAt": 1720000000000i64,
+            "updatedAt": 1720000600000i64,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_requirements_from_status() {
+        let requirements =
+            KycRequirements::from_status(&status("Action Request", Some("failed_allow_retry")));
+        assert!(requirements.action_required);
+        assert_eq!(
+            requirements.validation_status,
+            Some(ValidationStatus::FailedAllowRetry)
+        );
+        assert!(requirements.can_submit);
+        assert!(requirements.can_refund);
+
+        let requirements =
+            KycRequirements::from_status(&status("Action Request", Some("failed_deny_retry")));
+        assert!(!requirements.can_submit);
+        assert!(requirements.can_refund);
+
+        let requirements = KycRequirements::from_status(&status("Exchanging", None));
+        assert!(!requirements.action_required);
+        assert!(!requirements.can_submit);
+        assert!(!requirements.can_refund);
+    }
+}
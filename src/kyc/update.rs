@@ -2,14 +2,33 @@ use std::fmt;
 
 use reqwest::StatusCode;
 use serde::Serialize;
-use serde_json::Value;
 
-use crate::{client::Client, EasyBit, Error};
+use crate::{
+    client::Client, kyc::requirements::KycRequirements, orders::status::order_status,
+    response::api_error, Error,
+};
+// Image types accepted for KYC documents.
+const ACCEPTED_MEDIA_TYPES: [&str; 9] = [
+    "image/jpeg",
+    "image/jpg",
+    "image/png",
+    "image/webp",
+    "image/gif",
+    "image/bmp",
+    "image/tiff",
+    "image/heic",
+    "image/heif",
+];
+
 #[derive(Debug, Serialize)]
 pub enum DocumentType {
+    #[serde(rename = "PASSPORT")]
     Passport,
+    #[serde(rename = "ID_CARD")]
     IdCard,
+    #[serde(rename = "DRIVERS")]
     DriverLicense,
+    #[serde(rename = "RESIDENCE_PERMIT")]
     ResidencePermit,
 }
 
@@ -26,8 +45,11 @@ impl fmt::Display for DocumentType {
 
 #[derive(Debug, Serialize)]
 pub enum Side {
+    #[serde(rename = "FRONT_SIDE")]
     Front,
+    #[serde(rename = "BACK_SIDE")]
     Back,
+    #[serde(rename = "SINGLE_PAGE")]
     Single,
 }
 
@@ -56,6 +78,50 @@ pub struct Document {
     pub selfie: Option<Vec<String>>,
 }
 
+impl Document {
+    /**
+     * Checks the media of this document before it is uploaded.
+     * For every data URI in `uri` and `selfie`, the declared MIME type must be an accepted image type and the decoded size
+     * must be at most `max_bytes`. Media given as a URL is left for the server to check.
+     */
+    pub fn validate_media(&self, max_bytes: usize) -> Result<(), Error> {
+        self.uri
+            .iter()
+            .chain(self.selfie.iter().flatten())
+            .try_for_each(|uri| validate_data_uri(uri, max_bytes))
+    }
+}
+
+fn validate_data_uri(uri: &str, max_bytes: usize) -> Result<(), Error> {
+    let Some(data_uri) = uri.trim().strip_prefix("data:") else {
+        return Ok(());
+    };
+    let Some((header, data)) = data_uri.split_once(',') else {
+        return Err(Error::UnsupportedMediaType(
+            "malformed data URI".to_string(),
+        ));
+    };
+
+    let mut parameters = header.split(';');
+    let media_type = parameters.next().unwrap_or_default().to_lowercase();
+    if !ACCEPTED_MEDIA_TYPES.contains(&media_type.as_str()) {
+        return Err(Error::UnsupportedMediaType(media_type));
+    }
+
+    // The decoded size of base64 follows from its length, so the data does not need to be decoded.
+    let size = if parameters.any(|parameter| parameter.eq_ignore_ascii_case("base64")) {
+        let data = data.trim_end().trim_end_matches('=');
+        let length = data.chars().filter(|c| !c.is_ascii_whitespace()).count();
+        length * 3 / 4
+    } else {
+        data.len()
+    };
+    if size > max_bytes {
+        return Err(Error::MediaTooLarge { size, max_bytes });
+    }
+    Ok(())
+}
+
 /**
    **KYC Validation data.**
    - `country`: Country code for the user's country. [ISO 3166-1 alpha-3 standard](https://en.wikipedia.org/wiki/ISO_3166-1_alpha-3)
@@ -82,29 +148,286 @@ pub struct Proof {
     pub validationData: Option<ValidationData>,
 }
 
-// Untested function.
-#[allow(dead_code)]
+impl Proof {
+    /**
+     * Checks that the proof is structurally consistent before it is uploaded.
+     * Every document must have a document type and at least one of `uri` or `selfie`, and the country must be
+     * present when there are documents. Returns `Error::InvalidKycProof` naming the first inconsistency.
+     */
+    pub fn validate(&self) -> Result<(), Error> {
+        let invalid = |reason: String| Err(Error::InvalidKycProof { reason });
+
+        let Some(validation_data) = &self.validationData else {
+            return Ok(());
+        };
+        let documents = validation_data.documents.as_deref().unwrap_or_default();
+        let has_country = validation_data
+            .country
+            .as_deref()
+            .is_some_and(|country| !country.trim().is_empty());
+        if !documents.is_empty() && !has_country {
+            return invalid("documents are given without a country".to_string());
+        }
+
+        for (index, document) in documents.iter().enumerate() {
+            if document.documentType.is_none() {
+                return invalid(format!("document {} has no document type", index));
+            }
+            let has_selfie = document
+                .selfie
+                .as_ref()
+                .is_some_and(|selfie| !selfie.is_empty());
+            if document.uri.is_none() && !has_selfie {
+                return invalid(format!("document {} has neither a uri nor a selfie", index));
+            }
+        }
+        Ok(())
+    }
+}
+
 pub async fn update_kyc(client: &Client, proof: Proof) -> Result<(), Error> {
+    // Reject proofs the API would only refuse after the upload.
+    proof.validate()?;
+
     // Define the path.
     let path = "/updateOrder";
 
     // Make the POST request and set API key.
-    let response = reqwest::Client::new()
-        .post(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key())
-        .json(&proof)
-        .send()
-        .await?;
+    let response = client.send(client.post(path)?.json(&proof)).await?;
 
     let status: StatusCode = response.status();
 
     match status {
         StatusCode::OK => Ok(()),
-        _ => {
-            let json: Value = response.json().await?;
-            let error: EasyBit = serde_json::from_value(json)?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
+        _ => Err(api_error(response).await),
+    }
+}
+
+pub async fn retry_kyc(client: &Client, proof: Proof) -> Result<(), Error> {
+    // Only submit again if the current validation status allows it.
+    let status = order_status(client, proof.id.clone()).await?;
+    let requirements = KycRequirements::from_status(&status);
+    if !requirements.can_submit {
+        return Err(Error::RetryNotAllowed(format!(
+            "order status is {} and validation status is {}",
+            status.status,
+            status.validationStatus.as_deref().unwrap_or("null")
+        )));
+    }
+
+    update_kyc(client, proof).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{body_json, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    async fn server(validation_status: &str) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {
+                    "id": "order",
+                    "status": "Action Request",
+                    "receiveAmount": "1.9",
+                    "hashIn": null,
+                    "hashOut": null,
+                    "validationStatus": validation_status,
+                    "createdAt": 1720000000000i64,
+                    "updatedAt": 1720000600000i64,
+                }
+            })))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn proof() -> Proof {
+        Proof {
+            id: "order".to_string(),
+            userId: None,
+            validationData: None,
         }
     }
+
+    #[tokio::test]
+    async fn test_retry_kyc_allowed() {
+        let server = server("failed_allow_retry").await;
+        Mock::given(method("POST"))
+            .and(path("/updateOrder"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string());
+        retry_kyc(&client, proof()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_order_kyc() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/updateOrder"))
+            .and(body_json(serde_json::json!({
+                "id": "order",
+                "userId": "user",
+                "validationData": {
+                    "country": "SWE",
+                    "documents": [
+                        {
+                            "documentType": "PASSPORT",
+                            "side": "SINGLE_PAGE",
+                            "uri": "https://example.com/passport.png",
+                            "selfie": null
+                        },
+                        {
+                            "documentType": "ID_CARD",
+                            "side": "BACK_SIDE",
+                            "uri": null,
+                            "selfie": ["https://example.com/selfie.png"]
+                        }
+                    ]
+                }
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let proof = Proof {
+            id: "order".to_string(),
+            userId: Some("user".to_string()),
+            validationData: Some(ValidationData {
+                country: Some("SWE".to_string()),
+                documents: Some(vec![
+                    document("https://example.com/passport.png"),
+                    Document {
+                        documentType: Some(DocumentType::IdCard),
+                        side: Some(Side::Back),
+                        uri: None,
+                        selfie: Some(vec!["https://example.com/selfie.png".to_string()]),
+                    },
+                ]),
+            }),
+        };
+        let client = Client::new(server.uri(), "key".to_string());
+        client.update_order_kyc(proof).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retry_kyc_denied() {
+        let server = server("failed_deny_retry").await;
+        Mock::given(method("POST"))
+            .and(path("/updateOrder"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string());
+        match retry_kyc(&client, proof()).await {
+            Err(Error::RetryNotAllowed(_)) => (),
+            other => panic!("Expected a retry not allowed error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_kyc_missing_country() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/updateOrder"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let proof = Proof {
+            id: "order".to_string(),
+            userId: None,
+            validationData: Some(ValidationData {
+                country: None,
+                documents: Some(vec![document("https://example.com/passport.png")]),
+            }),
+        };
+        let client = Client::new(server.uri(), "key".to_string());
+        match update_kyc(&client, proof).await {
+            Err(Error::InvalidKycProof { reason }) => {
+                assert_eq!(reason, "documents are given without a country")
+            }
+            other => panic!("Expected an invalid KYC proof error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_proof() {
+        let mut proof = Proof {
+            id: "order".to_string(),
+            userId: None,
+            validationData: Some(ValidationData {
+                country: Some("SWE".to_string()),
+                documents: Some(vec![document("https://example.com/passport.png")]),
+            }),
+        };
+        assert!(proof.validate().is_ok());
+
+        let documents = proof
+            .validationData
+            .as_mut()
+            .and_then(|data| data.documents.as_mut())
+            .unwrap();
+        documents.push(Document {
+            documentType: None,
+            side: Some(Side::Front),
+            uri: Some("https://example.com/front.png".to_string()),
+            selfie: None,
+        });
+        assert!(matches!(
+            proof.validate(),
+            Err(Error::InvalidKycProof { reason }) if reason == "document 1 has no document type"
+        ));
+    }
+
+    fn document(uri: &str) -> Document {
+        Document {
+            documentType: Some(DocumentType::Passport),
+            side: Some(Side::Single),
+            uri: Some(uri.to_string()),
+            selfie: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_media_too_large() {
+        // 24 base64 characters with two padding characters decode to 16 bytes.
+        let document = document("data:image/png;base64,iVBORw0KGgoAAAANSUhEUg==");
+
+        assert!(document.validate_media(16).is_ok());
+        match document.validate_media(15) {
+            Err(Error::MediaTooLarge { size, max_bytes }) => {
+                assert_eq!((size, max_bytes), (16, 15));
+            }
+            other => panic!("Expected a media too large error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_media_unsupported_type() {
+        match document("data:application/pdf;base64,JVBERi0xLjQ=").validate_media(1024) {
+            Err(Error::UnsupportedMediaType(media_type)) => {
+                assert_eq!(media_type, "application/pdf")
+            }
+            other => panic!("Expected an unsupported media type error, got {:?}", other),
+        }
+
+        // Media given as a URL is not checked.
+        assert!(document("https://example.com/passport.pdf")
+            .validate_media(1)
+            .is_ok());
+    }
 }
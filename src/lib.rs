@@ -3,11 +3,22 @@ use std::fmt;
 use thiserror::Error;
 
 mod account;
+mod amount;
 mod currency;
 mod kyc;
 mod orders;
+mod rate_limit;
+mod redact;
+mod response;
+mod retry;
+#[cfg(test)]
+pub(crate) mod test_fixtures;
+mod timed;
+#[cfg(test)]
+mod wire_format;
 
-
+#[cfg(feature = "blocking")]
+pub mod blocking;
 /**
 # Easybit.io API client.
    Fully asynchronous wrapper for the easybit.io API.
@@ -18,7 +29,7 @@ Head over to [easybit.io](https://easybit.io) and sign up for an account. Once y
 request to retrieve an API key by sending an email to the EasyBit team.
 
 ### Usage
-```rust
+```rust,no_run
 use easybit::client::Client;
 use std::env;
 
@@ -34,34 +45,185 @@ async fn main() {
 pub mod client;
 
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default)]
 #[allow(non_snake_case)]
+#[non_exhaustive]
 /**
  * Common error structure for the EasyBit API.
+ * - `errorMessage`: Error message of the API
+ * - `errorCode`: Error code of the API
+ * - `status`: HTTP status of the response, `None` for errors raised by this library
+ * - `request_id`: Request ID the API returned in an `x-request-id` style header, worth quoting to EasyBit support
+ *
+ * New fields may be added, so the struct cannot be built with a struct literal outside this crate, use `EasyBit::new`.
  */
 pub struct EasyBit {
     pub errorMessage: String,
     pub errorCode: i32,
+    #[serde(skip)]
+    pub status: Option<reqwest::StatusCode>,
+    #[serde(skip)]
+    pub request_id: Option<String>,
+}
+
+impl EasyBit {
+    /**
+     * Create an error with the given EasyBit error code and message, without an HTTP status or request ID.
+     */
+    pub fn new(error_code: i32, error_message: String) -> EasyBit {
+        EasyBit {
+            errorMessage: error_message,
+            errorCode: error_code,
+            ..Default::default()
+        }
+    }
 }
 
 impl fmt::Display for EasyBit {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "EasyBit {}: {}", self.errorCode, self.errorMessage)
+        write!(f, "EasyBit {}: {}", self.errorCode, self.errorMessage)?;
+        match (self.status, &self.request_id) {
+            (Some(status), Some(request_id)) => {
+                write!(f, " (HTTP {}, request ID {})", status.as_u16(), request_id)
+            }
+            (Some(status), None) => write!(f, " (HTTP {})", status.as_u16()),
+            (None, Some(request_id)) => write!(f, " (request ID {})", request_id),
+            (None, None) => Ok(()),
+        }
     }
 }
 
+impl std::error::Error for EasyBit {}
+
 #[derive(Error, Debug)]
 /**
 ### Catch-all error structure for this library.
  * If a deserialization error occurs, it is likely that the underlying API has changed and the library needs to be updated. Kindly create an issue on GitHub.
+   With the `path-to-error` feature enabled, it is reported as an unexpected response error that includes the path of the failing field.
  * If a network error occurs, it is likely that the API is down or the URL is incorrect.
  * If an API error occurs, the API has returned an error message, and you should review your code.
+ * If an amount error occurs, an amount could not be parsed or used in a calculation.
+ * If an address error occurs, an address failed a client-side format or checksum check.
+ * If an address network mismatch error occurs, an address has the format of a different network than the one it is used on.
+ * If a media error occurs, a KYC document image is too large or not an accepted image type.
+ * If a host not allowed error occurs, a request was about to be sent to a host outside the allow-list of the client.
+ * If a connection string error occurs, a connection string passed to `Client::from_connection_string` is malformed.
+ * If a pair error occurs, an entry of the pair list does not have the expected format.
+ * If a timeout error occurs, an order did not reach a terminal status in the given time.
+ * If a suspicious refund address error occurs, an order was placed with the receive address as its refund address.
+ * If a fee error occurs, an extra fee is outside the range 0-0.1 or not a multiple of the 0.0001 step size. The error names the rule that was violated.
+ * If an incompatible API version error occurs, the API responded with a shape this library does not expect, see `Client::check_compatibility`.
+ * If a same currency error occurs, an order or quote was requested with the same currency to send and receive.
+ * If a network substituted error occurs, the API placed an order on a different network than the one requested.
+ * If a base URL error occurs, the URL a client was built with is empty, not an http(s) URL with a host, or has a query string.
+ * If a KYC proof error occurs, a proof is structurally inconsistent and would be rejected after upload, see `Proof::validate`.
+ * If a config error occurs, a client was built with settings that cannot be combined, such as a timeout with a custom HTTP client.
+ * If a runtime error occurs, the Tokio runtime of a blocking client could not be started.
+
+ Network, deserialization, runtime and unexpected response errors return the underlying `reqwest`, `serde_json` or
+ `std::io` error from `std::error::Error::source`, so error reporters such as `anyhow` can walk the chain.
  */
 pub enum Error {
     #[error("Network error: {0}")]
     NetworkError(#[from] reqwest::Error),
     #[error("JSON deserialization error: {0}")]
     DeserializeError(#[from] serde_json::Error),
-    #[error("{0}")]
+    #[error(transparent)]
     ApiError(EasyBit),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+    #[error("Amount {amount} is below the dust threshold {threshold}")]
+    AmountBelowDust {
+        amount: rust_decimal::Decimal,
+        threshold: rust_decimal::Decimal,
+    },
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+    #[error(
+        "Address network mismatch: {network} expects {expected} addresses, got {found} address"
+    )]
+    AddressNetworkMismatch {
+        network: String,
+        expected: crate::currency::validate_address::AddressFormat,
+        found: crate::currency::validate_address::AddressFormat,
+    },
+    #[error("KYC retry not allowed: {0}")]
+    RetryNotAllowed(String),
+    #[error("Media is {size} bytes, above the maximum of {max_bytes} bytes")]
+    MediaTooLarge { size: usize, max_bytes: usize },
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+    #[error("Host not allowed: {0}")]
+    HostNotAllowed(String),
+    #[error("Invalid connection string: {0}")]
+    InvalidConnectionString(String),
+    #[error("Invalid pair: {0}")]
+    InvalidPair(String),
+    #[error("Timed out: {0}")]
+    Timeout(String),
+    #[error("Refund address is the same as the receive address: {0}")]
+    SuspiciousRefundAddress(String),
+    #[error("Invalid fee {value}: {reason}")]
+    InvalidFee { value: f64, reason: String },
+    #[error("Incompatible API version: {0}")]
+    IncompatibleApiVersion(String),
+    #[error("Send and receive currency are both {0}")]
+    SameCurrency(String),
+    #[error("Requested network {requested}, but the order was placed on {actual}")]
+    NetworkSubstituted { requested: String, actual: String },
+    #[error("Invalid base URL: {0}")]
+    InvalidBaseUrl(String),
+    #[error("Invalid KYC proof: {reason}")]
+    InvalidKycProof { reason: String },
+    #[error("Invalid client configuration: {0}")]
+    InvalidConfig(String),
+    #[error("Failed to start the runtime: {0}")]
+    RuntimeError(#[source] std::io::Error),
+    #[error("Unexpected response at {path}: {source}")]
+    UnexpectedResponse {
+        path: String,
+        source: serde_json::Error,
+    },
+}
+
+impl Error {
+    /**
+     * Returns the EasyBit error code of an API error, and `None` for any other error.
+     */
+    pub fn error_code(&self) -> Option<i32> {
+        match self {
+            Error::ApiError(error) => Some(error.errorCode),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_error_source() {
+        let error = Error::from(serde_json::from_str::<serde_json::Value>("{").unwrap_err());
+        let source = error
+            .source()
+            .expect("a deserialization error has a source");
+        assert!(source.downcast_ref::<serde_json::Error>().is_some());
+        // The cause is kept in the message, for logs that only print the error itself.
+        assert!(error
+            .to_string()
+            .starts_with("JSON deserialization error: "));
+
+        let error = Error::ApiError(EasyBit::new(401, "Invalid API key".to_string()));
+        assert!(error.source().is_none());
+        assert_eq!(error.to_string(), "EasyBit 401: Invalid API key");
+    }
+
+    #[test]
+    fn test_error_code() {
+        let error = Error::ApiError(EasyBit::new(401, "Invalid API key".to_string()));
+        assert_eq!(error.error_code(), Some(401));
+        assert_eq!(Error::Timeout("order".to_string()).error_code(), None);
+    }
 }
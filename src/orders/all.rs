@@ -1,9 +1,24 @@
-use serde::Deserialize;
-use serde_json::Value;
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use crate::{client::Client, EasyBit, Error};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+use crate::{
+    amount::{parse_amount, parse_optional_amount, ratio},
+    client::Client,
+    currency::pair_info::{get_pair_info, Pair},
+    orders::{
+        create::Transaction,
+        status::{OrderStatus, ValidationStatus},
+    },
+    response::handle_data_response,
+    EasyBit, Error,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
 #[allow(non_snake_case)]
 /**
    ### Order Summary
@@ -76,6 +91,184 @@ pub struct Summary {
     pub updatedAt: i128,
 }
 
+impl Summary {
+    /**
+    ### Typed status of the order.
+    Statuses that this library does not know about yet are returned as `OrderStatus::Unknown`.
+    */
+    pub fn status_enum(&self) -> OrderStatus {
+        OrderStatus::from(self.status.as_str())
+    }
+
+    /**
+    ### Typed KYC validation status of the order.
+    Returns `None` if no validation has been requested.
+    */
+    pub fn validation_status_enum(&self) -> Option<ValidationStatus> {
+        ValidationStatus::parse_optional(self.validationStatus.as_deref())
+    }
+
+    /**
+    ### Finalized send amount as an exact decimal.
+    Returns `None` while the amount is not set, see `is_finalized`.
+    */
+    pub fn send_amount_decimal(&self) -> Result<Option<Decimal>, Error> {
+        parse_optional_amount("sendAmount", &self.sendAmount)
+    }
+
+    /**
+    ### Finalized receive amount as an exact decimal.
+    Returns `None` while the amount is not set, see `is_finalized`.
+    */
+    pub fn receive_amount_decimal(&self) -> Result<Option<Decimal>, Error> {
+        parse_optional_amount("receiveAmount", &self.receiveAmount)
+    }
+
+    /**
+    ### Network fee as an exact decimal.
+    */
+    pub fn network_fee_decimal(&self) -> Result<Decimal, Error> {
+        parse_amount("networkFee", &self.networkFee)
+    }
+
+    /**
+    ### Your earnings from the order as an exact decimal.
+    Returns `None` while the earnings are not set.
+    */
+    pub fn earned_decimal(&self) -> Result<Option<Decimal>, Error> {
+        parse_optional_amount("earned", &self.earned)
+    }
+
+    /**
+    ### Effective rate of the order.
+    Computed as `receiveAmount / sendAmount` from the finalized amounts.
+    Returns an error if an amount cannot be parsed or the send amount is zero.
+    */
+    pub fn effective_rate(&self) -> Result<Decimal, Error> {
+        ratio(
+            parse_amount("receiveAmount", &self.receiveAmount)?,
+            parse_amount("sendAmount", &self.sendAmount)?,
+        )
+    }
+
+    /**
+    ### Estimated rate of the order at the time of creation.
+    Computed as `estimatedReceiveAmount / estimatedSendAmount`.
+    Returns an error if an amount cannot be parsed or the send amount is zero.
+    */
+    pub fn estimated_rate(&self) -> Result<Decimal, Error> {
+        ratio(
+            parse_amount("estimatedReceiveAmount", &self.estimatedReceiveAmount)?,
+            parse_amount("estimatedSendAmount", &self.estimatedSendAmount)?,
+        )
+    }
+
+    /**
+    ### Whether the finalized amounts of the order are set.
+    Orders that are awaiting deposit have empty `sendAmount` and `receiveAmount` strings until the deposit is known.
+    */
+    pub fn is_finalized(&self) -> bool {
+        matches!(
+            (self.send_amount_decimal(), self.receive_amount_decimal()),
+            (Ok(Some(_)), Ok(Some(_)))
+        )
+    }
+
+    /**
+    ### Whether this order was placed for the same transaction.
+    Compares the currency pair, the estimated send amount and the receive address.
+    */
+    pub fn matches_transaction(&self, transaction: &Transaction) -> bool {
+        let same_amount = match (
+            parse_amount("estimatedSendAmount", &self.estimatedSendAmount),
+            Decimal::from_f64(transaction.amount),
+        ) {
+            (Ok(sent), Some(amount)) => sent == amount,
+            _ => false,
+        };
+        same_amount
+            && self.send.eq_ignore_ascii_case(&transaction.send)
+            && self.receive.eq_ignore_ascii_case(&transaction.receive)
+            && self.receiveAddress == transaction.receive_address
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+   ### Sort direction of an order list, by creation time.
+*/
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    /**
+     * Returns the sort direction as used by the API.
+     */
+    pub fn as_str(&self) -> &str {
+        match self {
+            SortDirection::Ascending => "ASC",
+            SortDirection::Descending => "DESC",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/**
+   ### Filters for the order list.
+   All fields are optional, unset fields are not sent.
+   - `id`: Order ID
+   - `limit`: Maximum number of orders to return
+   - `date_from`: Only orders created at or after this time
+   - `date_to`: Only orders created at or before this time
+   - `sort_direction`: Sort direction by creation time
+   - `status`: Only orders with this status
+*/
+pub struct OrderQuery {
+    pub id: Option<String>,
+    pub limit: Option<u32>,
+    pub date_from: Option<SystemTime>,
+    pub date_to: Option<SystemTime>,
+    pub sort_direction: Option<SortDirection>,
+    pub status: Option<OrderStatus>,
+}
+
+impl OrderQuery {
+    /**
+     * Returns the query parameters for the set fields. Times are sent as milliseconds since the Unix epoch.
+     */
+    pub fn to_query(&self) -> Vec<(&'static str, String)> {
+        let millis = |time: SystemTime| {
+            time.duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis()
+                .to_string()
+        };
+
+        let mut query = Vec::new();
+        if let Some(id) = &self.id {
+            query.push(("id", id.clone()));
+        }
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.to_string()));
+        }
+        if let Some(date_from) = self.date_from {
+            query.push(("dateFrom", millis(date_from)));
+        }
+        if let Some(date_to) = self.date_to {
+            query.push(("dateTo", millis(date_to)));
+        }
+        if let Some(sort_direction) = self.sort_direction {
+            query.push(("sortDirection", sort_direction.as_str().to_string()));
+        }
+        if let Some(status) = &self.status {
+            query.push(("status", status.as_str().to_string()));
+        }
+        query
+    }
+}
+
 pub async fn all_orders(
     client: &Client,
     id: Option<String>,
@@ -85,45 +278,516 @@ pub async fn all_orders(
     sort_direction: Option<String>,
     status: Option<String>,
 ) -> Result<Vec<Summary>, Error> {
+    // Only send the parameters that are set.
+    let query: Vec<(&str, String)> = [
+        ("id", id),
+        ("limit", limit),
+        ("dateFrom", date_from),
+        ("dateTo", date_to),
+        ("sortDirection", sort_direction),
+        ("status", status),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| value.map(|value| (key, value)))
+    .collect();
+
+    fetch_orders(client, &query).await
+}
+
+pub async fn query_orders(client: &Client, query: &OrderQuery) -> Result<Vec<Summary>, Error> {
+    fetch_orders(client, &query.to_query()).await
+}
+
+pub async fn get_order(client: &Client, order_id: String) -> Result<Summary, Error> {
+    let query = OrderQuery {
+        id: Some(order_id.clone()),
+        ..Default::default()
+    };
+    let orders = query_orders(client, &query).await?;
+    orders
+        .into_iter()
+        .find(|order| order.id == order_id)
+        .ok_or_else(|| Error::ApiError(EasyBit::new(404, "Order not found".to_string())))
+}
+
+async fn fetch_orders(client: &Client, query: &[(&str, String)]) -> Result<Vec<Summary>, Error> {
     // Define the path.
     let path = "/orders";
 
     // Make the GET request and set API key.
-    let request = reqwest::Client::new()
-        .get(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key())
-        .query(&[
-            ("id", id),
-            ("limit", limit),
-            ("dateFrom", date_from),
-            ("dateTo", date_to),
-            ("sortDirection", sort_direction),
-            ("status", status),
-        ])
-        .send()
-        .await?;
-
-    let json: Value = request.json().await?;
-
-    match json.get("data") {
-        Some(data) => {
-            log::info!("Raw status: {:?}", data);
-            let orders: Vec<Summary> = serde_json::from_value(data.clone())?;
-            Ok(orders)
+    let response = client.send(client.get(path)?.query(query)).await?;
+    handle_data_response(response).await
+}
+
+// Orders requested per page when paging through the order history.
+const PAGE_SIZE: u32 = 50;
+
+/**
+ * Pages through the order history from the newest order back, and returns the first order matching `predicate`.
+ * The API has no page parameter, so every following page is requested up to the creation time of the last order
+ * seen. Stops after `max_pages` pages, or when the history is exhausted.
+ */
+pub async fn find_order<F: Fn(&Summary) -> bool>(
+    client: &Client,
+    predicate: F,
+    max_pages: usize,
+) -> Result<Option<Summary>, Error> {
+    let mut date_to = None;
+    let mut seen: Vec<String> = Vec::new();
+    for _ in 0..max_pages {
+        let query = OrderQuery {
+            limit: Some(PAGE_SIZE),
+            date_to,
+            sort_direction: Some(SortDirection::Descending),
+            ..Default::default()
+        };
+        let page = query_orders(client, &query).await?;
+        let full = page.len() >= PAGE_SIZE as usize;
+
+        // Orders created at the cursor time are returned again, so skip the ones from the last page.
+        let orders: Vec<Summary> = page
+            .into_iter()
+            .filter(|order| !seen.contains(&order.id))
+            .collect();
+        let Some(last) = orders.last() else {
+            return Ok(None);
+        };
+        date_to = Some(UNIX_EPOCH + Duration::from_millis(last.createdAt.max(0) as u64));
+        seen = orders.iter().map(|order| order.id.clone()).collect();
+
+        if let Some(order) = orders.into_iter().find(|order| predicate(order)) {
+            return Ok(Some(order));
         }
-        None => {
-            let error: EasyBit = serde_json::from_value(json)?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
+        if !full {
+            return Ok(None);
         }
     }
+    Ok(None)
+}
+
+/**
+ * Groups orders by their status.
+ */
+pub fn group_by_status(orders: Vec<Summary>) -> HashMap<OrderStatus, Vec<Summary>> {
+    let mut groups: HashMap<OrderStatus, Vec<Summary>> = HashMap::new();
+    for order in orders {
+        groups.entry(order.status_enum()).or_default().push(order);
+    }
+    groups
+}
+
+pub async fn orders_by_status(
+    client: &Client,
+) -> Result<HashMap<OrderStatus, Vec<Summary>>, Error> {
+    let orders = all_orders(client, None, None, None, None, None, None).await?;
+    Ok(group_by_status(orders))
+}
+
+pub async fn get_incomplete_completions(client: &Client) -> Result<Vec<Summary>, Error> {
+    let orders = all_orders(client, None, None, None, None, None, None).await?;
+
+    // A completed order must have sent its funds, so a missing hash is a data inconsistency.
+    Ok(orders
+        .into_iter()
+        .filter(|order| {
+            order.status_enum() == OrderStatus::Complete
+                && order
+                    .hashOut
+                    .as_deref()
+                    .map(str::trim)
+                    .unwrap_or_default()
+                    .is_empty()
+        })
+        .collect())
+}
+
+pub async fn get_stuck_orders(client: &Client, grace: Duration) -> Result<Vec<Summary>, Error> {
+    let orders = all_orders(client, None, None, None, None, None, None).await?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i128;
+
+    // Fetch the pair information once per pair and network combination.
+    let mut pairs: HashMap<(String, String, String, String), Pair> = HashMap::new();
+    let mut stuck = Vec::new();
+    for order in orders {
+        let status = order.status_enum();
+        // Orders waiting for the customer are not held up by the exchange.
+        if status.is_terminal()
+            || matches!(
+                status,
+                OrderStatus::AwaitingDeposit | OrderStatus::ActionRequest
+            )
+        {
+            continue;
+        }
+
+        let key = (
+            order.send.clone(),
+            order.receive.clone(),
+            order.sendNetwork.clone(),
+            order.receiveNetwork.clone(),
+        );
+        if !pairs.contains_key(&key) {
+            let pair = get_pair_info(
+                client,
+                key.0.clone(),
+                key.1.clone(),
+                Some(key.2.clone()),
+                Some(key.3.clone()),
+                None,
+            )
+            .await?;
+            pairs.insert(key.clone(), pair);
+        }
+
+        let Some(processing_time) = pairs[&key].max_processing_time() else {
+            log::warn!(
+                "Cannot parse the processing time of {}, skipping order {}",
+                pairs[&key].processingTime,
+                order.id
+            );
+            continue;
+        };
+        let in_status = now - order.updatedAt;
+        if in_status > (processing_time + grace).as_millis() as i128 {
+            stuck.push(order);
+        }
+    }
+    Ok(stuck)
+}
+
+pub async fn find_recent_duplicate(
+    client: &Client,
+    transaction: &Transaction,
+    window: Duration,
+) -> Result<Option<Summary>, Error> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let since = now.saturating_sub(window).as_millis() as i128;
+
+    let orders = all_orders(
+        client,
+        None,
+        None,
+        Some(since.to_string()),
+        None,
+        None,
+        None,
+    )
+    .await?;
+
+    // Check the creation time as well, in case the API returns older orders.
+    Ok(orders
+        .into_iter()
+        .filter(|order| order.createdAt >= since && order.matches_transaction(transaction))
+        .max_by_key(|order| order.createdAt))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::client::Client;
+    use crate::{client::Client, orders::report::OrderReport, test_fixtures};
+    use serde_json::Value;
     use std::env;
+    use wiremock::{
+        matchers::{method, path, query_param, query_param_is_missing},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn summary(send_amount: &str, receive_amount: &str) -> Summary {
+        serde_json::from_value(summary_json(send_amount, receive_amount)).unwrap()
+    }
+
+    fn summary_json(send_amount: &str, receive_amount: &str) -> Value {
+        test_fixtures::summary_json("Complete", send_amount, receive_amount)
+    }
+
+    fn transaction(amount: f64) -> Transaction {
+        Transaction {
+            send: "BTC".to_string(),
+            receive: "ETH".to_string(),
+            amount,
+            receive_address: "0xeB2629a2734e272Bcc07BDA959863f316F4bD4Cf".to_string(),
+            receive_tag: None,
+            extra_fee_override: None,
+            vpm: None,
+            refund_address: None,
+            refund_tag: None,
+        }
+    }
+
+    #[test]
+    fn test_is_finalized() {
+        let mut json = summary_json("", "");
+        json["status"] = "Awaiting Deposit".into();
+        json["earned"] = "".into();
+        let awaiting: Summary = serde_json::from_value(json).unwrap();
+
+        assert!(!awaiting.is_finalized());
+        assert!(awaiting.effective_rate().is_err());
+        let report = OrderReport::from(&awaiting);
+        assert_eq!((report.send_amount, report.receive_amount), (None, None));
+
+        let complete: Summary = serde_json::from_value(summary_json("0.1", "1.9")).unwrap();
+        assert!(complete.is_finalized());
+        assert_eq!(awaiting.send_amount_decimal().unwrap(), None);
+        assert_eq!(awaiting.earned_decimal().unwrap(), None);
+        assert_eq!(complete.earned_decimal().unwrap(), Some(Decimal::new(2, 4)));
+        assert_eq!(
+            complete.receive_amount_decimal().unwrap(),
+            Some(Decimal::new(19, 1))
+        );
+    }
+
+    #[test]
+    fn test_order_query() {
+        let query = OrderQuery {
+            limit: Some(20),
+            date_from: Some(UNIX_EPOCH + Duration::from_millis(1720000000000)),
+            date_to: Some(UNIX_EPOCH + Duration::from_millis(1720086400000)),
+            sort_direction: Some(SortDirection::Descending),
+            status: Some(OrderStatus::AwaitingDeposit),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            query.to_query(),
+            vec![
+                ("limit", "20".to_string()),
+                ("dateFrom", "1720000000000".to_string()),
+                ("dateTo", "1720086400000".to_string()),
+                ("sortDirection", "DESC".to_string()),
+                ("status", "Awaiting Deposit".to_string()),
+            ]
+        );
+        assert!(OrderQuery::default().to_query().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_query_orders() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orders"))
+            .and(query_param("status", "Complete"))
+            .and(query_param("limit", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [summary_json("0.1", "1.9")]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        let query = OrderQuery {
+            limit: Some(1),
+            status: Some(OrderStatus::Complete),
+            ..Default::default()
+        };
+        let orders = query_orders(&client, &query).await.unwrap();
+        assert_eq!(orders.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orders"))
+            .and(query_param("id", "order"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [summary_json("0.1", "1.9")]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/orders"))
+            .and(query_param("id", "missing"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": [] })),
+            )
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        let order = get_order(&client, "order".to_string()).await.unwrap();
+        assert_eq!(order.id, "order");
+        match get_order(&client, "missing".to_string()).await {
+            Err(Error::ApiError(error)) => assert_eq!(error.errorCode, 404),
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_incomplete_completions() {
+        let mut consistent = summary_json("0.1", "1.9");
+        consistent["id"] = "consistent".into();
+        consistent["hashOut"] = "0xabc".into();
+        let mut inconsistent = summary_json("0.1", "1.9");
+        inconsistent["id"] = "inconsistent".into();
+        let mut pending = summary_json("0.1", "1.9");
+        pending["id"] = "pending".into();
+        pending["status"] = "Exchanging".into();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orders"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [consistent, inconsistent, pending]
+            })))
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        let orders = get_incomplete_completions(&client).await.unwrap();
+        let ids: Vec<&str> = orders.iter().map(|order| order.id.as_str()).collect();
+        assert_eq!(ids, vec!["inconsistent"]);
+    }
+
+    #[tokio::test]
+    async fn test_find_order() {
+        // Orders are created a second apart, newest first.
+        let order = |index: i64| {
+            let mut order = summary_json("0.1", "1.9");
+            order["id"] = format!("order-{}", index).into();
+            order["createdAt"] = (1720000000000i64 - index * 1000).into();
+            order
+        };
+        let first_page: Vec<Value> = (0..50).map(order).collect();
+        let mut second_page: Vec<Value> = (49..60).map(order).collect();
+        second_page[5]["receiveAddress"] = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".into();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orders"))
+            .and(query_param("limit", "50"))
+            .and(query_param("sortDirection", "DESC"))
+            .and(query_param_is_missing("dateTo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": first_page
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/orders"))
+            .and(query_param("dateTo", "1719999951000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": second_page
+            })))
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+        let by_address =
+            |order: &Summary| order.receiveAddress == "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
+        let found = find_order(&client, by_address, 5).await.unwrap();
+        assert_eq!(found.map(|order| order.id), Some("order-54".to_string()));
+
+        // The match is on the second page, which is never requested.
+        assert!(find_order(&client, by_address, 1).await.unwrap().is_none());
+
+        // The history ends with the short second page.
+        let missing = find_order(&client, |order| order.id == "order-99", 5)
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_stuck_orders() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let mut stuck = summary_json("0.1", "1.9");
+        stuck["id"] = "stuck".into();
+        stuck["status"] = "Exchanging".into();
+        stuck["updatedAt"] = (now - 3_600_000).into();
+        let mut healthy = summary_json("0.1", "1.9");
+        healthy["id"] = "healthy".into();
+        healthy["status"] = "Sending".into();
+        healthy["updatedAt"] = (now - 600_000).into();
+        let mut awaiting = summary_json("", "");
+        awaiting["id"] = "awaiting".into();
+        awaiting["status"] = "Awaiting Deposit".into();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orders"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [stuck, healthy, awaiting, summary_json("0.1", "1.9")]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/pairInfo"))
+            .and(query_param("send", "BTC"))
+            .and(query_param("receive", "ETH"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {
+                    "minimumAmount": "0.001",
+                    "maximumAmount": "10",
+                    "networkFee": "0.0001",
+                    "confirmations": 2,
+                    "processingTime": "5-30 minutes"
+                }
+            })))
+            // Once per call, as both active orders share the pair.
+            .expect(2)
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        // Both active orders are within 30 minutes plus a day.
+        let orders = get_stuck_orders(&client, Duration::from_secs(86_400))
+            .await
+            .unwrap();
+        assert!(orders.is_empty());
+
+        let orders = get_stuck_orders(&client, Duration::from_secs(600))
+            .await
+            .unwrap();
+        let ids: Vec<&str> = orders.iter().map(|order| order.id.as_str()).collect();
+        assert_eq!(ids, vec!["stuck"]);
+    }
+
+    #[tokio::test]
+    async fn test_find_recent_duplicate() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let mut recent = summary_json("0", "0");
+        recent["id"] = "recent".into();
+        recent["createdAt"] = (now - 60_000).into();
+        let mut old = summary_json("0", "0");
+        old["id"] = "old".into();
+        old["estimatedSendAmount"] = "0.2".into();
+        old["createdAt"] = (now - 3_600_000).into();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orders"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [recent, old]
+            })))
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+        let window = Duration::from_secs(600);
+
+        let duplicate = find_recent_duplicate(&client, &transaction(0.1), window)
+            .await
+            .unwrap();
+        assert_eq!(duplicate.map(|order| order.id), Some("recent".to_string()));
+
+        // The only order with this amount is outside the window.
+        let duplicate = find_recent_duplicate(&client, &transaction(0.2), window)
+            .await
+            .unwrap();
+        assert!(duplicate.is_none());
+    }
 
     #[tokio::test]
     async fn test_all_orders() {
@@ -131,4 +795,50 @@ mod tests {
         let result = all_orders(&client, None, None, None, None, None, None).await;
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_effective_rate() {
+        let order = summary("0.1", "1.8");
+
+        assert_eq!(order.effective_rate().unwrap(), Decimal::from(18));
+        assert_eq!(order.estimated_rate().unwrap(), Decimal::from(19));
+    }
+
+    #[test]
+    fn test_effective_rate_zero_send_amount() {
+        let order = summary("0", "1.8");
+
+        match order.effective_rate() {
+            Err(Error::InvalidAmount(_)) => (),
+            other => panic!("Expected an amount error, got {:?}", other),
+        }
+        assert_eq!(order.estimated_rate().unwrap(), Decimal::from(19));
+    }
+
+    #[test]
+    fn test_group_by_status() {
+        let orders = [
+            "Complete",
+            "Awaiting Deposit",
+            "Complete",
+            "Some New Status",
+        ]
+        .iter()
+        .map(|status| {
+            let mut order = summary("0.1", "1.8");
+            order.status = status.to_string();
+            order
+        })
+        .collect();
+
+        let groups = group_by_status(orders);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[&OrderStatus::Complete].len(), 2);
+        assert_eq!(groups[&OrderStatus::AwaitingDeposit].len(), 1);
+        assert_eq!(
+            groups[&OrderStatus::Unknown("Some New Status".to_string())].len(),
+            1
+        );
+    }
 }
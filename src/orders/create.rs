@@ -1,9 +1,20 @@
-use serde::Deserialize;
-use serde_json::Value;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 
-use crate::{client::Client, EasyBit, Error};
+use crate::{
+    amount::{check_dust, check_extra_fee, parse_amount},
+    client::Client,
+    currency::{
+        exchange_rate::{check_distinct_currencies, get_exchange_rate},
+        info::get_single_currency,
+        network_selection::resolve_networks,
+        validate_address::check_address_sanity,
+    },
+    response::handle_data_response,
+    Error,
+};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[allow(non_snake_case)]
 /**
    ### Order information.
@@ -41,6 +52,22 @@ pub struct Order {
     pub createdAt: i128,
 }
 
+impl Order {
+    /**
+     * Returns `sendAmount` as an exact decimal.
+     */
+    pub fn send_amount_decimal(&self) -> Result<Decimal, Error> {
+        parse_amount("sendAmount", &self.sendAmount)
+    }
+
+    /**
+     * Returns `receiveAmount` as an exact decimal.
+     */
+    pub fn receive_amount_decimal(&self) -> Result<Decimal, Error> {
+        parse_amount("receiveAmount", &self.receiveAmount)
+    }
+}
+
 #[derive(Debug)]
 /**
    ### User information.
@@ -54,16 +81,17 @@ pub struct User {
     pub payload: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 /**
    ### Network information.
     - `send_network`: Network code for the network to send on
     - `receive_network`: Network code for the network to receive on
-    - `receive_tag`: Tag to receive from
+    - `receive_tag`: Tag to receive from. Deprecated, use `Transaction::receive_tag` instead.
 */
 pub struct Network {
     pub send_network: Option<String>,
     pub receive_network: Option<String>,
+    #[deprecated(note = "use `Transaction::receive_tag` instead")]
     pub receive_tag: Option<String>,
 }
 
@@ -74,6 +102,7 @@ pub struct Network {
     - `receive`: Currency code for the currency to receive
     - `amount`: Amount of currency to send
     - `receive_address`: Address to receive from
+    - `receive_tag`: Tag to receive from. Takes precedence over the deprecated `Network::receive_tag`.
     - `extra_fee_override`: Override the extra fee
     - `vpm`: Volatility Protection Mode. "off" if not set.
     - `refund_address`: Address to refund to
@@ -84,61 +113,203 @@ pub struct Transaction {
     pub receive: String,
     pub amount: f64,
     pub receive_address: String,
+    pub receive_tag: Option<String>,
     pub extra_fee_override: Option<f64>,
     pub vpm: Option<String>,
     pub refund_address: Option<String>,
     pub refund_tag: Option<String>,
 }
 
+// JSON body of the order request. Field names are the ones used on the wire.
+#[derive(Debug, Serialize)]
+struct CreateOrderRequest<'a> {
+    #[serde(rename = "send")]
+    send: &'a str,
+    #[serde(rename = "receive")]
+    receive: &'a str,
+    #[serde(rename = "amount")]
+    amount: f64,
+    #[serde(rename = "receiveAddress")]
+    receive_address: &'a str,
+    #[serde(rename = "payload")]
+    payload: Option<&'a str>,
+    #[serde(rename = "userDeviceId")]
+    user_device_id: Option<&'a str>,
+    #[serde(rename = "userId")]
+    user_id: Option<&'a str>,
+    #[serde(rename = "sendNetwork")]
+    send_network: Option<&'a str>,
+    #[serde(rename = "receiveNetwork")]
+    receive_network: Option<&'a str>,
+    #[serde(rename = "receiveTag")]
+    receive_tag: Option<&'a str>,
+    #[serde(rename = "extraFeeOverride")]
+    extra_fee_override: Option<f64>,
+    #[serde(rename = "vpm")]
+    vpm: Option<&'a str>,
+    #[serde(rename = "refundAddress")]
+    refund_address: Option<&'a str>,
+    #[serde(rename = "refundTag")]
+    refund_tag: Option<&'a str>,
+}
+
+// Builds the body for the order request.
+#[allow(deprecated)]
+fn order_body<'a>(
+    transaction: &'a Transaction,
+    user: &'a User,
+    network: &'a Network,
+) -> CreateOrderRequest<'a> {
+    // Prefer the tag set on the transaction, falling back to the deprecated network one.
+    let receive_tag = transaction
+        .receive_tag
+        .as_deref()
+        .or(network.receive_tag.as_deref());
+
+    CreateOrderRequest {
+        send: &transaction.send,
+        receive: &transaction.receive,
+        amount: transaction.amount,
+        receive_address: &transaction.receive_address,
+        payload: user.payload.as_deref(),
+        user_device_id: user.user_device_id.as_deref(),
+        user_id: user.user_id.as_deref(),
+        send_network: network.send_network.as_deref(),
+        receive_network: network.receive_network.as_deref(),
+        receive_tag,
+        extra_fee_override: transaction.extra_fee_override,
+        vpm: transaction.vpm.as_deref(),
+        refund_address: transaction.refund_address.as_deref(),
+        refund_tag: transaction.refund_tag.as_deref(),
+    }
+}
+
+// Quotes the order and rejects it if the receive amount is below the dust threshold of the receive network.
+async fn ensure_above_dust(
+    client: &Client,
+    transaction: &Transaction,
+    network: &Network,
+    receive_network: &str,
+    threshold: rust_decimal::Decimal,
+) -> Result<(), Error> {
+    let (rate, currency) = tokio::try_join!(
+        get_exchange_rate(
+            client,
+            transaction.send.clone(),
+            transaction.receive.clone(),
+            transaction.amount,
+            network.send_network.clone(),
+            Some(receive_network.to_string()),
+            None,
+            transaction.extra_fee_override,
+        ),
+        get_single_currency(client, transaction.receive.clone()),
+    )?;
+
+    let decimals = currency.network(receive_network)?.receiveDecimals.max(0) as u32;
+
+    check_dust(
+        parse_amount("receiveAmount", &rate.receiveAmount)?,
+        decimals,
+        threshold,
+    )
+}
+
+// Refunding to the receive address is almost always a copy-paste mistake.
+fn check_refund_address(client: &Client, transaction: &Transaction) -> Result<(), Error> {
+    let Some(refund_address) = &transaction.refund_address else {
+        return Ok(());
+    };
+    if refund_address.trim() != transaction.receive_address.trim() {
+        return Ok(());
+    }
+    if client.get_reject_suspicious_refund_address() {
+        return Err(Error::SuspiciousRefundAddress(refund_address.clone()));
+    }
+    log::warn!(
+        "Refund address is the same as the receive address: {}",
+        refund_address
+    );
+    Ok(())
+}
+
 pub async fn create_order(
     client: &Client,
     transaction: Transaction,
     user: User,
-    network: Network,
+    mut network: Network,
 ) -> Result<Order, Error> {
+    check_distinct_currencies(&transaction.send, &transaction.receive)?;
+    check_address_sanity(&transaction.receive_address)?;
+    if let Some(fee) = transaction.extra_fee_override {
+        check_extra_fee(fee)?;
+    }
+    check_refund_address(client, &transaction)?;
+
+    (network.send_network, network.receive_network) = resolve_networks(
+        client,
+        &transaction.send,
+        &transaction.receive,
+        network.send_network,
+        network.receive_network,
+    )
+    .await?;
+
+    // Reject dust orders early if a threshold is set for the receive network.
+    if let Some(receive_network) = &network.receive_network {
+        if let Some(threshold) = client.get_dust_threshold(receive_network) {
+            ensure_above_dust(client, &transaction, &network, receive_network, threshold).await?;
+        }
+    }
+
     // Define the URL.
     let path = "/order";
 
     // Make the request.
-    let response = reqwest::Client::new()
-        .post(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key())
-        .json(&serde_json::json!({
-            "send": transaction.send,
-            "receive": transaction.receive,
-            "amount": transaction.amount,
-            "receiveAddress": transaction.receive_address,
-            "payload": user.payload,
-            "userDeviceId": user.user_device_id,
-            "userId": user.user_id,
-            "sendNetwork": network.send_network,
-            "receiveNetwork": network.receive_network,
-            "receiveTag": network.receive_tag,
-            "extraFeeOverride": transaction.extra_fee_override,
-            "vpm": transaction.vpm,
-            "refundAddress": transaction.refund_address,
-            "refundTag": transaction.refund_tag,
-        }))
-        .send()
-        .await?;
-    let json: Value = response.json().await?;
-    match json.get("data") {
-        Some(data) => {
-            let order: Order = serde_json::from_value(data.clone())?;
-            Ok(order)
-        }
-        None => {
-            let error: EasyBit = serde_json::from_value(json)?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
+    let request = client
+        .post(path)?
+        .json(&order_body(&transaction, &user, &network));
+    let response = client.send(request).await?;
+    handle_data_response(response).await
+}
+
+// Checks that the order is on a network that was explicitly requested, if any.
+fn check_network(requested: Option<&String>, actual: &str) -> Result<(), Error> {
+    match requested {
+        Some(requested) if !requested.trim().eq_ignore_ascii_case(actual.trim()) => {
+            Err(Error::NetworkSubstituted {
+                requested: requested.clone(),
+                actual: actual.to_string(),
+            })
         }
+        _ => Ok(()),
     }
 }
 
+pub async fn create_order_strict(
+    client: &Client,
+    transaction: Transaction,
+    user: User,
+    network: Network,
+) -> Result<Order, Error> {
+    let send_network = network.send_network.clone();
+    let receive_network = network.receive_network.clone();
+
+    let order = create_order(client, transaction, user, network).await?;
+    check_network(send_network.as_ref(), &order.sendNetwork)?;
+    check_network(receive_network.as_ref(), &order.receiveNetwork)?;
+    Ok(order)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_fixtures::order_json;
     use std::env;
+    use wiremock::{
+        matchers::{body_partial_json, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
     // The order section needs more testing.
     #[tokio::test]
     async fn test_place_simple_order() {
@@ -151,6 +322,7 @@ mod tests {
                 receive: "ETH".to_string(),
                 amount: 0.1,
                 receive_address: "0xeB2629a2734e272Bcc07BDA959863f316F4bD4Cf".to_string(),
+                receive_tag: None,
                 extra_fee_override: None,
                 vpm: None,
                 refund_address: None,
@@ -164,7 +336,7 @@ mod tests {
             Network {
                 send_network: None,
                 receive_network: None,
-                receive_tag: None,
+                ..Default::default()
             },
         )
         .await;
@@ -173,4 +345,288 @@ mod tests {
 
         assert!(order.is_ok());
     }
+
+    #[test]
+    fn test_check_refund_address() {
+        let transaction = |refund_address: &str| Transaction {
+            send: "BTC".to_string(),
+            receive: "ETH".to_string(),
+            amount: 0.1,
+            receive_address: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+            receive_tag: None,
+            extra_fee_override: None,
+            vpm: None,
+            refund_address: Some(refund_address.to_string()),
+            refund_tag: None,
+        };
+        let same = transaction("0x742d35Cc6634C0532925a3b844Bc454e4438f44e");
+        let distinct = transaction("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq");
+
+        // Only a warning by default.
+        let client = Client::new("http://localhost".to_string(), "key".to_string());
+        assert!(check_refund_address(&client, &same).is_ok());
+
+        let client = client.with_reject_suspicious_refund_address(true);
+        match check_refund_address(&client, &same) {
+            Err(Error::SuspiciousRefundAddress(address)) => {
+                assert_eq!(address, same.receive_address)
+            }
+            other => panic!(
+                "Expected a suspicious refund address error, got {:?}",
+                other
+            ),
+        }
+        assert!(check_refund_address(&client, &distinct).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_order_strict_substituted() {
+        let mut order = order_json();
+        order["receive"] = "USDT".into();
+        order["receiveNetwork"] = "BSC".into();
+        order["receiveAmount"] = "6000".into();
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": order
+            })))
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+        let transaction = || Transaction {
+            send: "BTC".to_string(),
+            receive: "USDT".to_string(),
+            amount: 0.1,
+            receive_address: "0xeB2629a2734e272Bcc07BDA959863f316F4bD4Cf".to_string(),
+            receive_tag: None,
+            extra_fee_override: None,
+            vpm: None,
+            refund_address: None,
+            refund_tag: None,
+        };
+        let user = || User {
+            user_device_id: Some("test".to_string()),
+            user_id: None,
+            payload: None,
+        };
+
+        let network = Network {
+            send_network: Some("btc".to_string()),
+            receive_network: Some("ETH".to_string()),
+            ..Default::default()
+        };
+        match create_order_strict(&client, transaction(), user(), network).await {
+            Err(Error::NetworkSubstituted { requested, actual }) => {
+                assert_eq!((requested.as_str(), actual.as_str()), ("ETH", "BSC"))
+            }
+            other => panic!("Expected a network substituted error, got {:?}", other),
+        }
+
+        // Networks that were not requested may be chosen by the server.
+        let order = create_order_strict(&client, transaction(), user(), Network::default())
+            .await
+            .unwrap();
+        assert_eq!(order.receiveNetwork, "BSC");
+    }
+
+    #[tokio::test]
+    async fn test_create_order_extra_fee_override() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .and(body_partial_json(
+                serde_json::json!({ "extraFeeOverride": 0.005 }),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "data": order_json() })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        // Out of range overrides must be rejected before anything is sent.
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+        let order = |extra_fee_override: f64| {
+            create_order(
+                &client,
+                Transaction {
+                    send: "BTC".to_string(),
+                    receive: "ETH".to_string(),
+                    amount: 0.1,
+                    receive_address: "0xeB2629a2734e272Bcc07BDA959863f316F4bD4Cf".to_string(),
+                    receive_tag: None,
+                    extra_fee_override: Some(extra_fee_override),
+                    vpm: None,
+                    refund_address: None,
+                    refund_tag: None,
+                },
+                User {
+                    user_device_id: Some("test".to_string()),
+                    user_id: None,
+                    payload: None,
+                },
+                Network::default(),
+            )
+        };
+
+        for extra_fee_override in [-0.001, 0.2] {
+            match order(extra_fee_override).await {
+                Err(Error::InvalidFee { value, .. }) => assert_eq!(value, extra_fee_override),
+                other => panic!("Expected an invalid fee error, got {:?}", other),
+            }
+        }
+        assert_eq!(order(0.005).await.unwrap().id, "order");
+    }
+
+    #[tokio::test]
+    async fn test_create_order_same_currency() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        let order = create_order(
+            &client,
+            Transaction {
+                send: "usdt".to_string(),
+                receive: "USDT".to_string(),
+                amount: 100.0,
+                receive_address: "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t".to_string(),
+                receive_tag: None,
+                extra_fee_override: None,
+                vpm: None,
+                refund_address: None,
+                refund_tag: None,
+            },
+            User {
+                user_device_id: Some("test".to_string()),
+                user_id: None,
+                payload: None,
+            },
+            Network::default(),
+        )
+        .await;
+        assert!(matches!(order, Err(Error::SameCurrency(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_order_invalid_receive_address() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        for receive_address in ["", "  \n"] {
+            let order = create_order(
+                &client,
+                Transaction {
+                    send: "BTC".to_string(),
+                    receive: "USDT".to_string(),
+                    amount: 0.1,
+                    receive_address: receive_address.to_string(),
+                    receive_tag: None,
+                    extra_fee_override: None,
+                    vpm: None,
+                    refund_address: None,
+                    refund_tag: None,
+                },
+                User {
+                    user_device_id: Some("test".to_string()),
+                    user_id: None,
+                    payload: None,
+                },
+                Network::default(),
+            )
+            .await;
+            assert!(matches!(order, Err(Error::InvalidAddress(_))));
+        }
+    }
+
+    #[test]
+    fn test_transaction_receive_tag() {
+        let body = serde_json::to_value(order_body(
+            &Transaction {
+                send: "XRP".to_string(),
+                receive: "XLM".to_string(),
+                amount: 100.0,
+                receive_address: "GAHK7EEG2WWHVKDNT4CEQFZGKF2LGDSW2IVM4S5DP42RBW3K6BTODB4A"
+                    .to_string(),
+                receive_tag: Some("123456".to_string()),
+                extra_fee_override: None,
+                vpm: None,
+                refund_address: None,
+                refund_tag: None,
+            },
+            &User {
+                user_device_id: Some("test".to_string()),
+                user_id: None,
+                payload: None,
+            },
+            &Network::default(),
+        ))
+        .unwrap();
+
+        assert_eq!(body["receiveTag"], "123456");
+    }
+
+    #[test]
+    fn test_order_body_wire_format() {
+        let transaction = Transaction {
+            send: "BTC".to_string(),
+            receive: "ETH".to_string(),
+            amount: 0.1,
+            receive_address: "0xeB2629a2734e272Bcc07BDA959863f316F4bD4Cf".to_string(),
+            receive_tag: None,
+            extra_fee_override: Some(0.5),
+            vpm: Some("on".to_string()),
+            refund_address: Some("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_string()),
+            refund_tag: None,
+        };
+        let user = User {
+            user_device_id: Some("device".to_string()),
+            user_id: Some("user".to_string()),
+            payload: None,
+        };
+        let network = Network {
+            send_network: Some("BTC".to_string()),
+            receive_network: None,
+            ..Default::default()
+        };
+
+        let body = serde_json::to_value(order_body(&transaction, &user, &network)).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "send": "BTC",
+                "receive": "ETH",
+                "amount": 0.1,
+                "receiveAddress": "0xeB2629a2734e272Bcc07BDA959863f316F4bD4Cf",
+                "payload": null,
+                "userDeviceId": "device",
+                "userId": "user",
+                "sendNetwork": "BTC",
+                "receiveNetwork": null,
+                "receiveTag": null,
+                "extraFeeOverride": 0.5,
+                "vpm": "on",
+                "refundAddress": "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+                "refundTag": null,
+            })
+        );
+    }
 }
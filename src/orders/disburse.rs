@@ -0,0 +1,151 @@
+use crate::{
+    client::Client,
+    currency::validate_address::validate_address_offline,
+    orders::create::{create_order, Network, Order, Transaction, User},
+    Error,
+};
+
+#[derive(Debug)]
+/**
+   ### Single order of a disbursement.
+   - `transaction`: Transaction information
+   - `user`: User information
+   - `network`: Network information
+*/
+pub struct DisbursementItem {
+    pub transaction: Transaction,
+    pub user: User,
+    pub network: Network,
+}
+
+#[derive(Debug)]
+/**
+   ### Outcome of a single order of a disbursement.
+   - `ValidationFailed`: The item failed the offline checks and no order was placed
+   - `CreationFailed`: The item passed the offline checks, but placing the order failed
+   - `Created`: The order was placed
+*/
+pub enum DisbursementOutcome {
+    ValidationFailed(Error),
+    CreationFailed(Error),
+    Created(Box<Order>),
+}
+
+// Offline checks of an item before its order is placed.
+fn validate_item(item: &DisbursementItem) -> Result<(), Error> {
+    let amount = item.transaction.amount;
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err(Error::InvalidAmount(format!(
+            "amount must be a positive number, got {}",
+            amount
+        )));
+    }
+    validate_address_offline(
+        &item.transaction.receive,
+        item.network.receive_network.as_deref(),
+        &item.transaction.receive_address,
+    )
+}
+
+pub async fn disburse(client: &Client, items: Vec<DisbursementItem>) -> Vec<DisbursementOutcome> {
+    let mut outcomes = Vec::with_capacity(items.len());
+
+    // Orders are placed one at a time so that a failing item does not affect the others.
+    for item in items {
+        if let Err(error) = validate_item(&item) {
+            outcomes.push(DisbursementOutcome::ValidationFailed(error));
+            continue;
+        }
+        let outcome = match create_order(client, item.transaction, item.user, item.network).await {
+            Ok(order) => DisbursementOutcome::Created(Box::new(order)),
+            Err(error) => DisbursementOutcome::CreationFailed(error),
+        };
+        outcomes.push(outcome);
+    }
+
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn item(receive_address: &str) -> DisbursementItem {
+        DisbursementItem {
+            transaction: Transaction {
+                send: "USDT".to_string(),
+                receive: "ETH".to_string(),
+                amount: 100.0,
+                receive_address: receive_address.to_string(),
+                receive_tag: None,
+                extra_fee_override: None,
+                vpm: None,
+                refund_address: None,
+                refund_tag: None,
+            },
+            user: User {
+                user_device_id: Some("payroll".to_string()),
+                user_id: None,
+                payload: None,
+            },
+            network: Network {
+                send_network: Some("TRX".to_string()),
+                receive_network: Some("ETH".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disburse() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {
+                    "id": "order",
+                    "send": "USDT",
+                    "receive": "ETH",
+                    "sendNetwork": "TRX",
+                    "receiveNetwork": "ETH",
+                    "sendAmount": "100",
+                    "receiveAmount": "0.03",
+                    "sendAddress": "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t",
+                    "sendTag": null,
+                    "receiveAddress": "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+                    "receiveTag": null,
+                    "refundAddress": null,
+                    "refundTag": null,
+                    "vpm": "off",
+                    "createdAt": 1720000000000i64
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        let outcomes = disburse(
+            &client,
+            vec![
+                // Invalid EIP-55 checksum.
+                item("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+                item("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+            ],
+        )
+        .await;
+
+        assert!(matches!(
+            outcomes[0],
+            DisbursementOutcome::ValidationFailed(Error::InvalidAddress(_))
+        ));
+        match &outcomes[1] {
+            DisbursementOutcome::Created(order) => assert_eq!(order.id, "order"),
+            other => panic!("Expected a created order, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    client::Client,
+    currency::info::{get_single_currency, Currency},
+    orders::{
+        all::get_order,
+        status::{order_status, Status},
+    },
+    Error,
+};
+
+#[derive(Debug)]
+/**
+   ### Order status with display metadata.
+   - `status`: Status of the order as returned by the API
+   - `send`: Currency code for the currency to send
+   - `send_name`: Display name of the currency to send
+   - `send_network`: Network code for the network to send on
+   - `send_network_name`: Display name of the network to send on
+   - `receive`: Currency code for the currency to receive
+   - `receive_name`: Display name of the currency to receive
+   - `receive_network`: Network code for the network to receive on
+   - `receive_network_name`: Display name of the network to receive on
+   - `hash_in_url`: Explorer link of the deposit transaction, if any
+   - `hash_out_url`: Explorer link of the payout transaction, if any
+*/
+pub struct EnrichedStatus {
+    pub status: Status,
+    pub send: String,
+    pub send_name: String,
+    pub send_network: String,
+    pub send_network_name: String,
+    pub receive: String,
+    pub receive_name: String,
+    pub receive_network: String,
+    pub receive_network_name: String,
+    pub hash_in_url: Option<String>,
+    pub hash_out_url: Option<String>,
+}
+
+// Returns the display name of a network and the explorer link of a transaction on it, if any.
+fn network_metadata(
+    currency: &Currency,
+    network: &str,
+    hash: Option<&str>,
+) -> Result<(String, Option<String>), Error> {
+    let explorer = currency.explorer_urls(network)?;
+    let name = currency.network(network)?.name.clone();
+    let url = hash
+        .filter(|hash| !hash.trim().is_empty())
+        .map(|hash| explorer.transaction_url(hash));
+    Ok((name, url))
+}
+
+pub async fn enriched_status(client: &Client, order_id: String) -> Result<EnrichedStatus, Error> {
+    // The status does not include the currencies, so the order is looked up as well.
+    let (status, order) = tokio::try_join!(
+        order_status(client, order_id.clone()),
+        get_order(client, order_id.clone()),
+    )?;
+
+    let (send, receive) = tokio::try_join!(
+        get_single_currency(client, order.send.clone()),
+        get_single_currency(client, order.receive.clone()),
+    )?;
+    let (send_network_name, hash_in_url) =
+        network_metadata(&send, &order.sendNetwork, status.hashIn.as_deref())?;
+    let (receive_network_name, hash_out_url) =
+        network_metadata(&receive, &order.receiveNetwork, status.hashOut.as_deref())?;
+
+    Ok(EnrichedStatus {
+        status,
+        send: order.send,
+        send_name: send.name,
+        send_network: order.sendNetwork,
+        send_network_name,
+        receive: order.receive,
+        receive_name: receive.name,
+        receive_network: order.receiveNetwork,
+        receive_network_name,
+        hash_in_url,
+        hash_out_url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{currency_json, network_json, summary_json};
+    use serde_json::Value;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn currency(code: &str, name: &str, explorer: &str) -> Value {
+        let mut network = network_json(code, name);
+        network["explorer"] = explorer.into();
+        network["explorerHash"] = format!("{}/tx/", explorer).into();
+        network["explorerAddress"] = format!("{}/address/", explorer).into();
+        currency_json(code, name, vec![network])
+    }
+
+    #[tokio::test]
+    async fn test_enriched_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {
+                    "id": "order",
+                    "status": "Sending",
+                    "receiveAmount": "1.9",
+                    "hashIn": "f4184fc5",
+                    "hashOut": null,
+                    "validationStatus": null,
+                    "createdAt": 1720000000000i64,
+                    "updatedAt": 1720000600000i64,
+                }
+            })))
+            .mount(&server)
+            .await;
+        let mut summary = summary_json("Sending", "0.1", "1.9");
+        summary["hashIn"] = "f4184fc5".into();
+        Mock::given(method("GET"))
+            .and(path("/orders"))
+            .and(query_param("id", "order"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [summary]
+            })))
+            .mount(&server)
+            .await;
+        for (code, name, explorer) in [
+            ("BTC", "Bitcoin", "https://blockchair.com/bitcoin"),
+            ("ETH", "Ethereum", "https://etherscan.io"),
+        ] {
+            Mock::given(method("GET"))
+                .and(path("/currencyList"))
+                .and(query_param("currency", code))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": [currency(code, name, explorer)]
+                })))
+                .mount(&server)
+                .await;
+        }
+        let client = Client::new(server.uri(), "key".to_string());
+
+        let enriched = enriched_status(&client, "order".to_string()).await.unwrap();
+
+        assert_eq!(enriched.status.status, "Sending");
+        assert_eq!(enriched.send_name, "Bitcoin");
+        assert_eq!(enriched.receive_network_name, "Ethereum");
+        assert_eq!(
+            enriched.hash_in_url.as_deref(),
+            Some("https://blockchair.com/bitcoin/tx/f4184fc5")
+        );
+        assert_eq!(enriched.hash_out_url, None);
+    }
+}
@@ -0,0 +1,223 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::{
+    amount::parse_optional_amount,
+    orders::{all::Summary, status::OrderStatus},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+/**
+   ### Kind of a ledger entry.
+   - `DepositReceived`: The deposit of the customer was received
+   - `Exchange`: The deposit was exchanged into the receive currency
+   - `NetworkFee`: The network fee of the outgoing transaction
+   - `FeeEarned`: Your earnings from the order
+   - `AmountSent`: The exchanged amount was sent to the customer
+   - `Refund`: The deposit was refunded to the customer
+*/
+pub enum LedgerEntryKind {
+    DepositReceived,
+    Exchange,
+    NetworkFee,
+    FeeEarned,
+    AmountSent,
+    Refund,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+/**
+   ### Side of a ledger entry.
+*/
+pub enum LedgerSide {
+    Debit,
+    Credit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/**
+   ### Ledger entry of an order.
+   Entries are booked against the order, so the debits and credits of a completed order balance per currency.
+   - `order_id`: Order ID
+   - `kind`: What the entry records
+   - `side`: Debit for funds flowing into the order, credit for funds flowing out of it
+   - `currency`: Currency code of the amount
+   - `amount`: Amount of the entry, never negative
+*/
+pub struct LedgerEntry {
+    pub order_id: String,
+    pub kind: LedgerEntryKind,
+    pub side: LedgerSide,
+    pub currency: String,
+    pub amount: Decimal,
+}
+
+impl Summary {
+    /**
+    ### Ledger entries of the order for double-entry bookkeeping.
+    Entries are only booked once the order is settled, so that they always balance. When the order is complete, the
+    deposit is debited in the send currency and credited as exchanged, and the proceeds are debited in the receive
+    currency, then credited as network fee, fee earned and amount sent. The API does not state the currency of
+    `networkFee` and `earned`, both are booked in the receive currency. When the order was refunded, including by the
+    volatility protection, the deposit is debited and credited as refunded in the send currency.
+
+    Orders that are in progress, failed or overdue have no entries, as are orders whose amounts are not known yet.
+    Zero amounts are left out. Amounts that cannot be parsed are treated as not known.
+    */
+    pub fn ledger_entries(&self) -> Vec<LedgerEntry> {
+        let amount = |field: &str, value: &str| parse_optional_amount(field, value).ok().flatten();
+        let mut entries = Vec::new();
+        let mut push =
+            |kind: LedgerEntryKind, side: LedgerSide, currency: &str, amount: Decimal| {
+                if !amount.is_zero() {
+                    entries.push(LedgerEntry {
+                        order_id: self.id.clone(),
+                        kind,
+                        side,
+                        currency: currency.to_string(),
+                        amount,
+                    });
+                }
+            };
+
+        let Some(send_amount) = amount("sendAmount", &self.sendAmount) else {
+            return entries;
+        };
+        let receive_amount = amount("receiveAmount", &self.receiveAmount);
+        let receive_amount = match (self.status_enum(), receive_amount) {
+            (OrderStatus::Complete, Some(receive_amount)) => receive_amount,
+            (OrderStatus::Refund | OrderStatus::VolatilityProtection, _) => {
+                push(
+                    LedgerEntryKind::DepositReceived,
+                    LedgerSide::Debit,
+                    &self.send,
+                    send_amount,
+                );
+                push(
+                    LedgerEntryKind::Refund,
+                    LedgerSide::Credit,
+                    &self.send,
+                    send_amount,
+                );
+                return entries;
+            }
+            _ => return entries,
+        };
+        let network_fee = amount("networkFee", &self.networkFee).unwrap_or_default();
+        let earned = amount("earned", &self.earned).unwrap_or_default();
+
+        push(
+            LedgerEntryKind::DepositReceived,
+            LedgerSide::Debit,
+            &self.send,
+            send_amount,
+        );
+        push(
+            LedgerEntryKind::Exchange,
+            LedgerSide::Credit,
+            &self.send,
+            send_amount,
+        );
+        push(
+            LedgerEntryKind::Exchange,
+            LedgerSide::Debit,
+            &self.receive,
+            receive_amount + network_fee + earned,
+        );
+        push(
+            LedgerEntryKind::NetworkFee,
+            LedgerSide::Credit,
+            &self.receive,
+            network_fee,
+        );
+        push(
+            LedgerEntryKind::FeeEarned,
+            LedgerSide::Credit,
+            &self.receive,
+            earned,
+        );
+        push(
+            LedgerEntryKind::AmountSent,
+            LedgerSide::Credit,
+            &self.receive,
+            receive_amount,
+        );
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::test_fixtures::summary_json;
+
+    fn summary(status: &str, send_amount: &str, receive_amount: &str) -> Summary {
+        serde_json::from_value(summary_json(status, send_amount, receive_amount)).unwrap()
+    }
+
+    // Sums the debits and credits of the entries per currency, debits counting positive.
+    fn balances(entries: &[LedgerEntry]) -> HashMap<&str, Decimal> {
+        let mut balances: HashMap<&str, Decimal> = HashMap::new();
+        for entry in entries {
+            let balance = balances.entry(entry.currency.as_str()).or_default();
+            match entry.side {
+                LedgerSide::Debit => *balance += entry.amount,
+                LedgerSide::Credit => *balance -= entry.amount,
+            }
+        }
+        balances
+    }
+
+    #[test]
+    fn test_ledger_entries_balance() {
+        let entries = summary("Complete", "0.1", "1.8").ledger_entries();
+
+        let kinds: Vec<LedgerEntryKind> = entries.iter().map(|entry| entry.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                LedgerEntryKind::DepositReceived,
+                LedgerEntryKind::Exchange,
+                LedgerEntryKind::Exchange,
+                LedgerEntryKind::NetworkFee,
+                LedgerEntryKind::FeeEarned,
+                LedgerEntryKind::AmountSent,
+            ]
+        );
+        assert_eq!(entries[2].amount, Decimal::new(18012, 4));
+
+        let balances = balances(&entries);
+        assert_eq!(balances.len(), 2);
+        assert!(balances.values().all(Decimal::is_zero));
+    }
+
+    #[test]
+    fn test_ledger_entries_refund() {
+        for status in ["Refund", "Volatility Protection"] {
+            let entries = summary(status, "0.1", "").ledger_entries();
+            let kinds: Vec<LedgerEntryKind> = entries.iter().map(|entry| entry.kind).collect();
+            assert_eq!(
+                kinds,
+                vec![LedgerEntryKind::DepositReceived, LedgerEntryKind::Refund]
+            );
+            assert_eq!(entries[1].amount, Decimal::new(1, 1));
+            assert!(balances(&entries).values().all(Decimal::is_zero));
+        }
+    }
+
+    #[test]
+    fn test_ledger_entries_unsettled() {
+        assert!(summary("Awaiting Deposit", "", "")
+            .ledger_entries()
+            .is_empty());
+
+        // A known deposit is not booked until the order is settled, as nothing would offset it.
+        for status in ["Exchanging", "Failed", "Request Overdue"] {
+            let entries = summary(status, "0.1", "").ledger_entries();
+            assert!(entries.is_empty());
+            assert!(balances(&entries).values().all(Decimal::is_zero));
+        }
+    }
+}
@@ -1,3 +1,11 @@
 pub mod all;
 pub mod create;
+pub mod disburse;
+pub mod enriched;
+pub mod ledger;
+pub mod receipt;
+pub mod report;
+pub mod statistics;
 pub mod status;
+pub mod wait;
+pub mod watch;
@@ -0,0 +1,200 @@
+use std::fmt::Write;
+
+use chrono::DateTime;
+
+use crate::{
+    currency::info::ExplorerUrls,
+    orders::{all::Summary, create::Order},
+};
+
+// Formats a timestamp in milliseconds as a UTC date and time.
+fn format_timestamp(timestamp: i128) -> String {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .map_or_else(
+            || timestamp.to_string(),
+            |time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        )
+}
+
+// Formats an address with its tag, linking it if an explorer is given.
+fn format_address(address: &str, tag: Option<&str>, explorer: Option<&ExplorerUrls>) -> String {
+    let mut formatted = address.to_string();
+    if let Some(tag) = tag {
+        let _ = write!(formatted, " (tag {})", tag);
+    }
+    if let Some(explorer) = explorer {
+        let _ = write!(formatted, " <{}>", explorer.address_url(address));
+    }
+    formatted
+}
+
+// Formats a transaction hash, linking it if an explorer is given.
+fn format_hash(hash: Option<&str>, explorer: Option<&ExplorerUrls>) -> String {
+    match (hash, explorer) {
+        (Some(hash), Some(explorer)) => format!("{} <{}>", hash, explorer.transaction_url(hash)),
+        (Some(hash), None) => hash.to_string(),
+        (None, _) => "pending".to_string(),
+    }
+}
+
+impl Summary {
+    /**
+    ### Human readable receipt of the order.
+    Lists the pair, amounts, status, addresses, transaction hashes and timestamps, one per line.
+    */
+    pub fn receipt(&self) -> String {
+        self.receipt_with_explorers(None, None)
+    }
+
+    /**
+    ### Human readable receipt of the order, with explorer links.
+    Like `receipt`, with links to the addresses and transactions on the explorers of the send and receive networks.
+
+    **Parameters**
+    - `send_explorer`: Explorer URLs of the send network, see `Client::get_explorer_urls`
+    - `receive_explorer`: Explorer URLs of the receive network
+    */
+    pub fn receipt_with_explorers(
+        &self,
+        send_explorer: Option<&ExplorerUrls>,
+        receive_explorer: Option<&ExplorerUrls>,
+    ) -> String {
+        let mut receipt = String::new();
+        let _ = writeln!(receipt, "Order {}", self.id);
+        let _ = writeln!(
+            receipt,
+            "Pair: {} ({}) -> {} ({})",
+            self.send, self.sendNetwork, self.receive, self.receiveNetwork
+        );
+        let _ = writeln!(receipt, "Sent: {} {}", self.sendAmount, self.send);
+        let _ = writeln!(receipt, "Received: {} {}", self.receiveAmount, self.receive);
+        let _ = writeln!(receipt, "Status: {}", self.status);
+        let _ = writeln!(
+            receipt,
+            "Deposit address: {}",
+            format_address(&self.sendAddress, self.sendTag.as_deref(), send_explorer)
+        );
+        let _ = writeln!(
+            receipt,
+            "Receive address: {}",
+            format_address(
+                &self.receiveAddress,
+                self.receiveTag.as_deref(),
+                receive_explorer
+            )
+        );
+        let _ = writeln!(
+            receipt,
+            "Deposit transaction: {}",
+            format_hash(self.hashIn.as_deref(), send_explorer)
+        );
+        let _ = writeln!(
+            receipt,
+            "Payout transaction: {}",
+            format_hash(self.hashOut.as_deref(), receive_explorer)
+        );
+        let _ = writeln!(receipt, "Created: {}", format_timestamp(self.createdAt));
+        let _ = write!(receipt, "Updated: {}", format_timestamp(self.updatedAt));
+        receipt
+    }
+}
+
+impl Order {
+    /**
+    ### Human readable receipt of the order at the time it was placed.
+    Lists the pair, estimated amounts, addresses and creation time, one per line.
+    */
+    pub fn receipt(&self) -> String {
+        self.receipt_with_explorers(None, None)
+    }
+
+    /**
+    ### Human readable receipt of the order at the time it was placed, with explorer links.
+
+    **Parameters**
+    - `send_explorer`: Explorer URLs of the send network, see `Client::get_explorer_urls`
+    - `receive_explorer`: Explorer URLs of the receive network
+    */
+    pub fn receipt_with_explorers(
+        &self,
+        send_explorer: Option<&ExplorerUrls>,
+        receive_explorer: Option<&ExplorerUrls>,
+    ) -> String {
+        let mut receipt = String::new();
+        let _ = writeln!(receipt, "Order {}", self.id);
+        let _ = writeln!(
+            receipt,
+            "Pair: {} ({}) -> {} ({})",
+            self.send, self.sendNetwork, self.receive, self.receiveNetwork
+        );
+        let _ = writeln!(receipt, "Send: {} {}", self.sendAmount, self.send);
+        let _ = writeln!(
+            receipt,
+            "Estimated receive: {} {}",
+            self.receiveAmount, self.receive
+        );
+        let _ = writeln!(
+            receipt,
+            "Deposit address: {}",
+            format_address(&self.sendAddress, self.sendTag.as_deref(), send_explorer)
+        );
+        let _ = writeln!(
+            receipt,
+            "Receive address: {}",
+            format_address(
+                &self.receiveAddress,
+                self.receiveTag.as_deref(),
+                receive_explorer
+            )
+        );
+        let _ = write!(receipt, "Created: {}", format_timestamp(self.createdAt));
+        receipt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::summary_json;
+
+    fn summary() -> Summary {
+        let mut summary = summary_json("Complete", "0.1", "1.9");
+        summary["hashIn"] = "f4184fc5".into();
+        serde_json::from_value(summary).unwrap()
+    }
+
+    #[test]
+    fn test_summary_receipt() {
+        let receipt = summary().receipt();
+
+        assert!(receipt.contains("Order order"));
+        assert!(receipt.contains("Pair: BTC (BTC) -> ETH (ETH)"));
+        assert!(receipt.contains("Sent: 0.1 BTC"));
+        assert!(receipt.contains("Received: 1.9 ETH"));
+        assert!(receipt.contains("Status: Complete"));
+        assert!(receipt.contains("Deposit transaction: f4184fc5"));
+        assert!(receipt.contains("Payout transaction: pending"));
+        assert!(receipt.contains("Created: 2024-07-03 09:46:40 UTC"));
+        assert!(receipt.contains("Updated: 2024-07-03 09:56:40 UTC"));
+    }
+
+    #[test]
+    fn test_summary_receipt_with_explorers() {
+        let explorer = ExplorerUrls {
+            explorer: "https://blockchair.com/bitcoin".to_string(),
+            explorer_hash: "https://blockchair.com/bitcoin/transaction/".to_string(),
+            explorer_address: "https://blockchair.com/bitcoin/address/".to_string(),
+            explorer_contract: None,
+        };
+        let receipt = summary().receipt_with_explorers(Some(&explorer), None);
+
+        assert!(receipt.contains(
+            "Deposit transaction: f4184fc5 <https://blockchair.com/bitcoin/transaction/f4184fc5>"
+        ));
+        assert!(receipt.contains(
+            "Deposit address: 1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa <https://blockchair.com/bitcoin/address/1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa>"
+        ));
+    }
+}
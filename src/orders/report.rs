@@ -0,0 +1,135 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::{
+    amount::parse_optional_amount,
+    orders::{
+        all::Summary,
+        status::{OrderStatus, ValidationStatus},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/**
+   ### Order report.
+   Flat, typed version of an order `Summary` for reporting and invoicing.
+   Amounts that are empty or cannot be parsed, for example before the order is finalized, are `None`.
+   - `id`: Order ID
+   - `send_currency`: Currency code for the currency to send
+   - `receive_currency`: Currency code for the currency to receive
+   - `send_network`: Network code for the network to send on
+   - `receive_network`: Network code for the network to receive on
+   - `send_amount`: Finalized amount of currency to send
+   - `receive_amount`: Finalized amount of currency to receive
+   - `estimated_send_amount`: Estimated amount of currency to send at the time of order creation
+   - `estimated_receive_amount`: Estimated amount of currency to receive at the time of order creation
+   - `send_address`: Address to send to
+   - `send_tag`: Tag to send to
+   - `receive_address`: Address to receive from
+   - `receive_tag`: Tag to receive from
+   - `refund_address`: Address to refund to
+   - `refund_tag`: Tag to refund to
+   - `vpm`: Volatility Protection Mode. "off" if not set.
+   - `status`: Order status
+   - `validation_status`: KYC validation status, `None` if no validation has been requested
+   - `hash_in`: Hash of the transaction in
+   - `hash_out`: Hash of the transaction out
+   - `network_fee`: Network fee
+   - `earned`: Your earnings from the order
+   - `created_at`: Timestamp the order was created (milliseconds)
+   - `updated_at`: Timestamp the order was last updated (milliseconds)
+*/
+pub struct OrderReport {
+    pub id: String,
+    pub send_currency: String,
+    pub receive_currency: String,
+    pub send_network: String,
+    pub receive_network: String,
+    pub send_amount: Option<Decimal>,
+    pub receive_amount: Option<Decimal>,
+    pub estimated_send_amount: Option<Decimal>,
+    pub estimated_receive_amount: Option<Decimal>,
+    pub send_address: String,
+    pub send_tag: Option<String>,
+    pub receive_address: String,
+    pub receive_tag: Option<String>,
+    pub refund_address: Option<String>,
+    pub refund_tag: Option<String>,
+    pub vpm: String,
+    pub status: OrderStatus,
+    pub validation_status: Option<ValidationStatus>,
+    pub hash_in: Option<String>,
+    pub hash_out: Option<String>,
+    pub network_fee: Option<Decimal>,
+    pub earned: Option<Decimal>,
+    pub created_at: i128,
+    pub updated_at: i128,
+}
+
+impl From<&Summary> for OrderReport {
+    fn from(summary: &Summary) -> Self {
+        let amount = |field: &str, value: &str| parse_optional_amount(field, value).ok().flatten();
+
+        OrderReport {
+            id: summary.id.clone(),
+            send_currency: summary.send.clone(),
+            receive_currency: summary.receive.clone(),
+            send_network: summary.sendNetwork.clone(),
+            receive_network: summary.receiveNetwork.clone(),
+            send_amount: amount("sendAmount", &summary.sendAmount),
+            receive_amount: amount("receiveAmount", &summary.receiveAmount),
+            estimated_send_amount: amount("estimatedSendAmount", &summary.estimatedSendAmount),
+            estimated_receive_amount: amount(
+                "estimatedReceiveAmount",
+                &summary.estimatedReceiveAmount,
+            ),
+            send_address: summary.sendAddress.clone(),
+            send_tag: summary.sendTag.clone(),
+            receive_address: summary.receiveAddress.clone(),
+            receive_tag: summary.receiveTag.clone(),
+            refund_address: summary.refundAddress.clone(),
+            refund_tag: summary.refundTag.clone(),
+            vpm: summary.vpm.clone(),
+            status: summary.status_enum(),
+            validation_status: summary.validation_status_enum(),
+            hash_in: summary.hashIn.clone(),
+            hash_out: summary.hashOut.clone(),
+            network_fee: amount("networkFee", &summary.networkFee),
+            earned: amount("earned", &summary.earned),
+            created_at: summary.createdAt,
+            updated_at: summary.updatedAt,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::summary_json;
+
+    #[test]
+    fn test_report_from_summary() {
+        let mut summary = summary_json("Action Request", "0.1", "1.8");
+        summary["hashIn"] = "hash".into();
+        summary["networkFee"] = "".into();
+        summary["validationStatus"] = "failed_deny_retry".into();
+        let summary: Summary = serde_json::from_value(summary).unwrap();
+
+        let report = OrderReport::from(&summary);
+
+        assert_eq!(report.send_currency, "BTC");
+        assert_eq!(report.send_amount, Some(Decimal::new(1, 1)));
+        assert_eq!(report.receive_amount, Some(Decimal::new(18, 1)));
+        assert_eq!(report.network_fee, None);
+        assert_eq!(report.earned, Some(Decimal::new(2, 4)));
+        assert_eq!(report.status, OrderStatus::ActionRequest);
+        assert_eq!(
+            report.validation_status,
+            Some(ValidationStatus::FailedDenyRetry)
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["status"], "Action Request");
+        assert_eq!(json["validation_status"], "failed_deny_retry");
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rust_decimal::Decimal;
+
+use crate::{
+    amount::{parse_amount, parse_optional_amount, ratio},
+    client::Client,
+    orders::{
+        all::{query_orders, OrderQuery, Summary},
+        status::OrderStatus,
+    },
+    Error,
+};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/**
+   ### Aggregate statistics over a list of orders.
+   Amounts are only summed for completed orders, as other orders do not have finalized amounts.
+   - `send_volume`: Total amount sent per currency code
+   - `receive_volume`: Total amount received per currency code
+   - `average_slippage`: Average difference between the estimated and finalized receive amount of completed orders, in percent of the estimate. `None` if no order could be measured.
+   - `success_rate`: Completed orders in percent of all orders in a terminal status. `None` if no order is in a terminal status.
+*/
+pub struct OrderStatistics {
+    pub send_volume: HashMap<String, Decimal>,
+    pub receive_volume: HashMap<String, Decimal>,
+    pub average_slippage: Option<Decimal>,
+    pub success_rate: Option<Decimal>,
+}
+
+/**
+ * Computes aggregate statistics over a list of orders.
+ * Orders whose amounts cannot be parsed are left out of the amount sums and the slippage.
+ */
+pub fn order_statistics(orders: &[Summary]) -> OrderStatistics {
+    let mut statistics = OrderStatistics::default();
+    let mut slippages: Vec<Decimal> = Vec::new();
+    let mut terminal = 0;
+    let mut completed = 0;
+
+    for order in orders {
+        let status = order.status_enum();
+        if status.is_terminal() {
+            terminal += 1;
+        }
+        if status != OrderStatus::Complete {
+            continue;
+        }
+        completed += 1;
+
+        let (Ok(send_amount), Ok(receive_amount)) = (
+            parse_amount("sendAmount", &order.sendAmount),
+            parse_amount("receiveAmount", &order.receiveAmount),
+        ) else {
+            continue;
+        };
+
+        *statistics
+            .send_volume
+            .entry(order.send.clone())
+            .or_default() += send_amount;
+        *statistics
+            .receive_volume
+            .entry(order.receive.clone())
+            .or_default() += receive_amount;
+
+        if let Ok(estimated) = parse_amount("estimatedReceiveAmount", &order.estimatedReceiveAmount)
+        {
+            if let Ok(slippage) = ratio(estimated - receive_amount, estimated) {
+                slippages.push(slippage * Decimal::ONE_HUNDRED);
+            }
+        }
+    }
+
+    if !slippages.is_empty() {
+        statistics.average_slippage =
+            Some(slippages.iter().sum::<Decimal>() / Decimal::from(slippages.len()));
+    }
+    if terminal > 0 {
+        statistics.success_rate =
+            Some(Decimal::from(completed) * Decimal::ONE_HUNDRED / Decimal::from(terminal));
+    }
+
+    statistics
+}
+
+/**
+ * Sums the earnings of orders per (send, receive) currency pair.
+ * Orders with empty earnings are skipped, and orders whose earnings cannot be parsed are logged and skipped.
+ */
+pub fn sum_earnings_by_pair(orders: &[Summary]) -> HashMap<(String, String), Decimal> {
+    let mut earnings: HashMap<(String, String), Decimal> = HashMap::new();
+    for order in orders {
+        let earned = match parse_optional_amount("earned", &order.earned) {
+            Ok(Some(earned)) => earned,
+            Ok(None) => continue,
+            Err(error) => {
+                log::warn!("Skipping earnings of order {}: {}", order.id, error);
+                continue;
+            }
+        };
+        *earnings
+            .entry((order.send.clone(), order.receive.clone()))
+            .or_default() += earned;
+    }
+    earnings
+}
+
+pub async fn earnings_by_pair(
+    client: &Client,
+    from: SystemTime,
+    to: SystemTime,
+) -> Result<HashMap<(String, String), Decimal>, Error> {
+    let orders = query_orders(
+        client,
+        &OrderQuery {
+            date_from: Some(from),
+            date_to: Some(to),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    // Check the creation time as well, in case the API returns orders outside the range.
+    let millis = |time: SystemTime| {
+        time.duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i128
+    };
+    let (from, to) = (millis(from), millis(to));
+    let orders: Vec<Summary> = orders
+        .into_iter()
+        .filter(|order| (from..=to).contains(&order.createdAt))
+        .collect();
+    Ok(sum_earnings_by_pair(&orders))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::summary_json;
+
+    fn summary(
+        send: &str,
+        receive: &str,
+        status: &str,
+        send_amount: &str,
+        receive_amount: &str,
+        estimated_receive_amount: &str,
+    ) -> Summary {
+        let mut summary = summary_json(status, send_amount, receive_amount);
+        summary["send"] = send.into();
+        summary["receive"] = receive.into();
+        summary["sendNetwork"] = send.into();
+        summary["receiveNetwork"] = receive.into();
+        summary["estimatedSendAmount"] = send_amount.into();
+        summary["estimatedReceiveAmount"] = estimated_receive_amount.into();
+        summary["networkFee"] = "0".into();
+        summary["earned"] = "0".into();
+        serde_json::from_value(summary).unwrap()
+    }
+
+    #[test]
+    fn test_sum_earnings_by_pair() {
+        let earning = |send: &str, receive: &str, earned: &str| {
+            let mut order = summary(send, receive, "Complete", "1", "1", "1");
+            order.earned = earned.to_string();
+            order
+        };
+        let orders = vec![
+            earning("BTC", "ETH", "0.0002"),
+            earning("BTC", "ETH", "0.0003"),
+            earning("ETH", "USDT", "1.5"),
+            earning("ETH", "USDT", ""),
+            earning("ETH", "USDT", "n/a"),
+        ];
+
+        let earnings = sum_earnings_by_pair(&orders);
+
+        assert_eq!(earnings.len(), 2);
+        assert_eq!(
+            earnings[&("BTC".to_string(), "ETH".to_string())],
+            Decimal::new(5, 4)
+        );
+        assert_eq!(
+            earnings[&("ETH".to_string(), "USDT".to_string())],
+            Decimal::new(15, 1)
+        );
+    }
+
+    #[test]
+    fn test_order_statistics() {
+        let orders = vec![
+            summary("BTC", "ETH", "Complete", "0.1", "1.8", "2"),
+            summary("BTC", "ETH", "Complete", "0.2", "4", "4"),
+            summary("ETH", "BTC", "Complete", "2", "0.1", "0.1"),
+            summary("BTC", "ETH", "Failed", "0.5", "10", "10"),
+            summary("BTC", "ETH", "Awaiting Deposit", "", "", "10"),
+        ];
+
+        let statistics = order_statistics(&orders);
+
+        assert_eq!(statistics.send_volume["BTC"], Decimal::new(3, 1));
+        assert_eq!(statistics.send_volume["ETH"], Decimal::from(2));
+        assert_eq!(statistics.receive_volume["ETH"], Decimal::new(58, 1));
+        assert_eq!(statistics.receive_volume["BTC"], Decimal::new(1, 1));
+        // Only the first of the three completed orders slipped, by 10%.
+        assert_eq!(
+            statistics.average_slippage.unwrap().round_dp(6),
+            Decimal::new(3333333, 6)
+        );
+        // Three of the four terminal orders completed.
+        assert_eq!(statistics.success_rate, Some(Decimal::from(75)));
+    }
+}
@@ -1,9 +1,19 @@
-use serde::Deserialize;
-use serde_json::Value;
+use std::fmt;
 
-use crate::{client::Client, EasyBit, Error};
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::{stream, StreamExt, TryStreamExt};
+use rust_decimal::Decimal;
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
-#[derive(Debug, Deserialize)]
+use crate::{
+    amount::parse_optional_amount, client::Client, currency::pair_info::Pair,
+    response::handle_data_response, Error,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
 #[allow(non_snake_case)]
 /**
     ### Status information.
@@ -42,31 +52,254 @@ pub struct Status {
     pub updatedAt: i128,
 }
 
+impl Status {
+    /**
+     * Returns `receiveAmount` as an exact decimal, or `None` while it is not set.
+     */
+    pub fn receive_amount_decimal(&self) -> Result<Option<Decimal>, Error> {
+        parse_optional_amount("receiveAmount", &self.receiveAmount)
+    }
+
+    /**
+     * Returns the typed status of the order.
+     */
+    pub fn status_enum(&self) -> OrderStatus {
+        OrderStatus::from(self.status.as_str())
+    }
+
+    /**
+     * Returns the typed KYC validation status of the order, `None` if no validation has been requested.
+     */
+    pub fn validation_status_enum(&self) -> Option<ValidationStatus> {
+        ValidationStatus::parse_optional(self.validationStatus.as_deref())
+    }
+
+    /**
+     * Returns when the order is expected to arrive at the latest: `createdAt` plus the upper bound of the
+     * processing time of the pair. Returns `None` if the processing time cannot be parsed.
+     */
+    pub fn estimated_arrival(&self, pair: &Pair) -> Option<DateTime<Utc>> {
+        let created = DateTime::from_timestamp_millis(i64::try_from(self.createdAt).ok()?)?;
+        let processing_time = TimeDelta::from_std(pair.max_processing_time()?).ok()?;
+        created.checked_add_signed(processing_time)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/**
+    ### Order status.
+    Typed version of the `status` string returned by the API.
+    Statuses that this library does not know about yet are kept in `Unknown`.
+*/
+pub enum OrderStatus {
+    AwaitingDeposit,
+    ConfirmingDeposit,
+    Exchanging,
+    Sending,
+    Complete,
+    Refund,
+    Failed,
+    VolatilityProtection,
+    ActionRequest,
+    RequestOverdue,
+    Unknown(String),
+}
+
+impl OrderStatus {
+    /**
+     * Returns the status string as used by the API.
+     */
+    pub fn as_str(&self) -> &str {
+        match self {
+            OrderStatus::AwaitingDeposit => "Awaiting Deposit",
+            OrderStatus::ConfirmingDeposit => "Confirming Deposit",
+            OrderStatus::Exchanging => "Exchanging",
+            OrderStatus::Sending => "Sending",
+            OrderStatus::Complete => "Complete",
+            OrderStatus::Refund => "Refund",
+            OrderStatus::Failed => "Failed",
+            OrderStatus::VolatilityProtection => "Volatility Protection",
+            OrderStatus::ActionRequest => "Action Request",
+            OrderStatus::RequestOverdue => "Request Overdue",
+            OrderStatus::Unknown(status) => status,
+        }
+    }
+
+    /**
+     * Returns true if the order will not change status anymore.
+     * Terminal statuses are Complete, Refund, Failed, Volatility Protection and Request Overdue.
+     */
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Complete
+                | OrderStatus::Refund
+                | OrderStatus::Failed
+                | OrderStatus::VolatilityProtection
+                | OrderStatus::RequestOverdue
+        )
+    }
+}
+
+impl From<&str> for OrderStatus {
+    fn from(status: &str) -> Self {
+        match status {
+            "Awaiting Deposit" => OrderStatus::AwaitingDeposit,
+            "Confirming Deposit" => OrderStatus::ConfirmingDeposit,
+            "Exchanging" => OrderStatus::Exchanging,
+            "Sending" => OrderStatus::Sending,
+            "Complete" => OrderStatus::Complete,
+            "Refund" => OrderStatus::Refund,
+            "Failed" => OrderStatus::Failed,
+            "Volatility Protection" => OrderStatus::VolatilityProtection,
+            "Action Request" => OrderStatus::ActionRequest,
+            "Request Overdue" => OrderStatus::RequestOverdue,
+            other => OrderStatus::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for OrderStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+struct OrderStatusVisitor;
+
+impl Visitor<'_> for OrderStatusVisitor {
+    type Value = OrderStatus;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an order status string")
+    }
+
+    fn visit_str<E: de::Error>(self, status: &str) -> Result<OrderStatus, E> {
+        Ok(OrderStatus::from(status))
+    }
+}
+
+impl<'de> Deserialize<'de> for OrderStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(OrderStatusVisitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/**
+    ### KYC validation status.
+    Typed version of the `validationStatus` string returned by the API. A `null` validation status is represented as `None`.
+    Validation statuses that this library does not know about yet are kept in `Unknown`.
+*/
+pub enum ValidationStatus {
+    Awaiting,
+    Pending,
+    FailedAllowRetry,
+    FailedDenyRetry,
+    Complete,
+    Failed,
+    Unknown(String),
+}
+
+impl ValidationStatus {
+    /**
+     * Returns the validation status string as used by the API.
+     */
+    pub fn as_str(&self) -> &str {
+        match self {
+            ValidationStatus::Awaiting => "awaiting",
+            ValidationStatus::Pending => "pending",
+            ValidationStatus::FailedAllowRetry => "failed_allow_retry",
+            ValidationStatus::FailedDenyRetry => "failed_deny_retry",
+            ValidationStatus::Complete => "complete",
+            ValidationStatus::Failed => "failed",
+            ValidationStatus::Unknown(status) => status,
+        }
+    }
+
+    /**
+     * Parses an optional `validationStatus` string. `None`, and the string "null" that the API documents as a possible
+     * value, are returned as `None`.
+     */
+    pub fn parse_optional(status: Option<&str>) -> Option<ValidationStatus> {
+        status
+            .filter(|status| *status != "null")
+            .map(ValidationStatus::from)
+    }
+}
+
+impl From<&str> for ValidationStatus {
+    fn from(status: &str) -> Self {
+        match status {
+            "awaiting" => ValidationStatus::Awaiting,
+            "pending" => ValidationStatus::Pending,
+            "failed_allow_retry" => ValidationStatus::FailedAllowRetry,
+            "failed_deny_retry" => ValidationStatus::FailedDenyRetry,
+            "complete" => ValidationStatus::Complete,
+            "failed" => ValidationStatus::Failed,
+            other => ValidationStatus::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ValidationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for ValidationStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+struct ValidationStatusVisitor;
+
+impl Visitor<'_> for ValidationStatusVisitor {
+    type Value = ValidationStatus;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a validation status string")
+    }
+
+    fn visit_str<E: de::Error>(self, status: &str) -> Result<ValidationStatus, E> {
+        Ok(ValidationStatus::from(status))
+    }
+}
+
+impl<'de> Deserialize<'de> for ValidationStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(ValidationStatusVisitor)
+    }
+}
+
 pub async fn order_status(client: &Client, id: String) -> Result<Status, Error> {
     // Define the path.
     let path = "/orderStatus";
 
     // Make the GET request and set API key. The query should only contain items that are not None.
-    let response = reqwest::Client::new()
-        .get(format!("{}{}", client.get_url(), path))
-        .header("API-KEY", client.get_api_key())
-        .query(&[("id", id)])
-        .send()
-        .await?;
-
-    let json: Value = response.json().await?;
-    match json.get("data") {
-        Some(data) => {
-            log::info!("Raw status: {:?}", data);
-            let order: Status = serde_json::from_value(data.clone())?;
-            Ok(order)
-        }
-        None => {
-            let error: EasyBit = serde_json::from_value(json)?;
-            log::error!("{:?}", error);
-            Err(Error::ApiError(error))
-        }
-    }
+    let response = client.send(client.get(path)?.query(&[("id", id)])).await?;
+
+    handle_data_response(response).await
+}
+
+/**
+ * Retrieves the status of many orders, with at most `Client::get_status_concurrency` requests in flight.
+ * The statuses are returned in the order of the IDs.
+ */
+pub async fn order_statuses(client: &Client, ids: &[String]) -> Result<Vec<Status>, Error> {
+    stream::iter(ids)
+        .map(|id| order_status(client, id.clone()))
+        .buffered(client.get_status_concurrency())
+        .try_collect()
+        .await
 }
 
 #[cfg(test)]
@@ -74,6 +307,146 @@ mod tests {
     use super::*;
     use crate::client::Client;
     use std::env;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[test]
+    fn test_order_status_deserialize() {
+        let statuses: Vec<OrderStatus> =
+            serde_json::from_value(serde_json::json!(["Confirming Deposit", "Refunded Twice"]))
+                .unwrap();
+        assert_eq!(
+            statuses,
+            vec![
+                OrderStatus::ConfirmingDeposit,
+                OrderStatus::Unknown("Refunded Twice".to_string())
+            ]
+        );
+        assert!(serde_json::from_value::<OrderStatus>(serde_json::json!(3)).is_err());
+    }
+
+    #[test]
+    fn test_validation_status_enum() {
+        let validation_statuses: Vec<Option<ValidationStatus>> =
+            serde_json::from_value(serde_json::json!([
+                null,
+                "failed_deny_retry",
+                "failed_allow_retry",
+                "escalated"
+            ]))
+            .unwrap();
+        assert_eq!(
+            validation_statuses,
+            vec![
+                None,
+                Some(ValidationStatus::FailedDenyRetry),
+                Some(ValidationStatus::FailedAllowRetry),
+                Some(ValidationStatus::Unknown("escalated".to_string())),
+            ]
+        );
+
+        let mut status: Status = serde_json::from_value(serde_json::json!({
+            "id": "order",
+            "status": "Action Request",
+            "receiveAmount": "1.9",
+            "hashIn": null,
+            "hashOut": null,
+            "validationStatus": "failed_deny_retry",
+            "createdAt": 1720000000000i64,
+            "updatedAt": 1720000600000i64,
+        }))
+        .unwrap();
+        assert_eq!(
+            status.validation_status_enum(),
+            Some(ValidationStatus::FailedDenyRetry)
+        );
+        status.validationStatus = Some("null".to_string());
+        assert_eq!(status.validation_status_enum(), None);
+    }
+
+    #[test]
+    fn test_estimated_arrival() {
+        let status = Status {
+            id: "order".to_string(),
+            status: "Awaiting Deposit".to_string(),
+            receiveAmount: "1.9".to_string(),
+            hashIn: None,
+            hashOut: None,
+            validationStatus: None,
+            createdAt: 1720000000000,
+            updatedAt: 1720000000000,
+        };
+        let mut pair = Pair {
+            minimumAmount: "0.001".to_string(),
+            maximumAmount: "10".to_string(),
+            networkFee: "0.0001".to_string(),
+            confirmations: 2,
+            processingTime: "30 minutes".to_string(),
+        };
+
+        assert_eq!(
+            status.estimated_arrival(&pair).unwrap().to_rfc3339(),
+            "2024-07-03T10:16:40+00:00"
+        );
+
+        pair.processingTime = "soon".to_string();
+        assert_eq!(status.estimated_arrival(&pair), None);
+    }
+
+    #[tokio::test]
+    async fn test_order_statuses() {
+        let server = MockServer::start().await;
+        for (id, status) in [
+            ("a", "Complete"),
+            ("b", "Exchanging"),
+            ("c", "Awaiting Deposit"),
+        ] {
+            Mock::given(method("GET"))
+                .and(path("/orderStatus"))
+                .and(query_param("id", id))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": {
+                        "id": id,
+                        "status": status,
+                        "receiveAmount": "",
+                        "hashIn": null,
+                        "hashOut": null,
+                        "validationStatus": null,
+                        "createdAt": 1720000000000i64,
+                        "updatedAt": 1720000000000i64,
+                    }
+                })))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .and(query_param("id", "missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "success": 0,
+                "errorCode": 404,
+                "errorMessage": "Order not found"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string()).with_status_concurrency(2);
+        let ids = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let statuses = order_statuses(&client, &ids).await.unwrap();
+
+        let ids: Vec<&str> = statuses.iter().map(|status| status.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(statuses[1].status_enum(), OrderStatus::Exchanging);
+
+        match order_statuses(&client, &["missing".to_string()]).await {
+            Err(error) => assert_eq!(error.error_code(), Some(404)),
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+    }
 
     #[tokio::test]
     async fn test_order_status() {
@@ -87,6 +460,7 @@ mod tests {
                 receive: "ETH".to_string(),
                 amount: 0.1,
                 receive_address: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+                receive_tag: None,
                 extra_fee_override: None,
                 vpm: None,
                 refund_address: None,
@@ -100,7 +474,7 @@ mod tests {
             crate::orders::create::Network {
                 send_network: None,
                 receive_network: None,
-                receive_tag: None,
+                ..Default::default()
             },
         )
         .await
@@ -0,0 +1,284 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::time::Instant;
+
+use crate::{
+    client::Client,
+    kyc::{
+        requirements::KycRequirements,
+        update::{update_kyc, Proof},
+    },
+    orders::status::{order_status, Status},
+    Error,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/**
+   ### Polling policy for waiting on an order.
+   - `interval`: Time between status polls
+   - `timeout`: Maximum time to wait for a terminal status
+*/
+pub struct PollConfig {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        PollConfig {
+            interval: Duration::from_secs(10),
+            timeout: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+pub async fn wait_for_order(
+    client: &Client,
+    order_id: String,
+    config: PollConfig,
+) -> Result<Status, Error> {
+    let deadline = Instant::now() + config.timeout;
+
+    loop {
+        let status = order_status(client, order_id.clone()).await?;
+        if status.status_enum().is_terminal() {
+            return Ok(status);
+        }
+
+        if Instant::now() + config.interval > deadline {
+            return Err(Error::Timeout(format!(
+                "order {} is still {} after {:?}",
+                order_id, status.status, config.timeout
+            )));
+        }
+        tokio::time::sleep(config.interval).await;
+    }
+}
+
+pub async fn wait_for_order_with_kyc(
+    client: &Client,
+    order_id: String,
+    timeout: Duration,
+    kyc_handler: impl Fn(&Status) -> Option<Proof>,
+) -> Result<Status, Error> {
+    let deadline = Instant::now() + timeout;
+    let poll_interval = client.get_poll_interval();
+
+    // Validation status of the last poll. A proof is submitted once per validation round, and a new round starts
+    // whenever the validation status changes, e.g. when a resubmitted proof fails again after being pending.
+    let mut observed: Option<Option<String>> = None;
+    let mut submitted = false;
+
+    loop {
+        let status = order_status(client, order_id.clone()).await?;
+        if status.status_enum().is_terminal() {
+            return Ok(status);
+        }
+
+        if observed.as_ref() != Some(&status.validationStatus) {
+            observed = Some(status.validationStatus.clone());
+            submitted = false;
+        }
+
+        let requirements = KycRequirements::from_status(&status);
+        if requirements.can_submit && !submitted {
+            if let Some(proof) = kyc_handler(&status) {
+                update_kyc(client, proof).await?;
+                submitted = true;
+            }
+        }
+
+        if Instant::now() + poll_interval > deadline {
+            return Err(Error::Timeout(format!(
+                "order {} is still {} after {:?}",
+                order_id, status.status, timeout
+            )));
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{body_partial_json, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn status(status: &str, validation_status: Option<&str>) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": {
+                "id": "order",
+                "status": status,
+                "receiveAmount": "1.9",
+                "hashIn": null,
+                "hashOut": null,
+                "validationStatus": validation_status,
+                "createdAt": 1720000000000i64,
+                "updatedAt": 1720000600000i64,
+            }
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .respond_with(status("Exchanging", None))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .respond_with(status("Volatility Protection", None))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string());
+        let config = PollConfig {
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_secs(5),
+        };
+        let status = wait_for_order(&client, "order".to_string(), config)
+            .await
+            .unwrap();
+
+        assert_eq!(status.status, "Volatility Protection");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_order_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .respond_with(status("Awaiting Deposit", None))
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string());
+        let config = PollConfig {
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(50),
+        };
+        let result = wait_for_order(&client, "order".to_string(), config).await;
+
+        assert!(matches!(result, Err(Error::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_order_with_kyc() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .respond_with(status("Action Request", Some("awaiting")))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .respond_with(status("Complete", Some("complete")))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/updateOrder"))
+            .and(body_partial_json(serde_json::json!({ "id": "order" })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string())
+            .with_poll_interval(Duration::from_millis(10));
+        let status = wait_for_order_with_kyc(
+            &client,
+            "order".to_string(),
+            Duration::from_secs(5),
+            |status| {
+                Some(Proof {
+                    id: status.id.clone(),
+                    userId: None,
+                    validationData: None,
+                })
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(status.status, "Complete");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_order_with_kyc_failed_again() {
+        let server = MockServer::start().await;
+        // The first proof fails, the resubmitted proof is pending and fails again, and the third one is accepted.
+        for (validation_status, polls) in [
+            ("failed_allow_retry", 2),
+            ("pending", 2),
+            ("failed_allow_retry", 2),
+        ] {
+            Mock::given(method("GET"))
+                .and(path("/orderStatus"))
+                .respond_with(status("Action Request", Some(validation_status)))
+                .up_to_n_times(polls)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .respond_with(status("Complete", Some("complete")))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/updateOrder"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string())
+            .with_poll_interval(Duration::from_millis(10));
+        let status = wait_for_order_with_kyc(
+            &client,
+            "order".to_string(),
+            Duration::from_secs(5),
+            |status| {
+                Some(Proof {
+                    id: status.id.clone(),
+                    userId: None,
+                    validationData: None,
+                })
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(status.status, "Complete");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_order_with_kyc_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .respond_with(status("Action Request", Some("failed_deny_retry")))
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri(), "key".to_string())
+            .with_poll_interval(Duration::from_millis(10));
+        let result = wait_for_order_with_kyc(
+            &client,
+            "order".to_string(),
+            Duration::from_millis(50),
+            |_| panic!("Retries are denied, the handler must not be called"),
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::Timeout(_))));
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+use futures::{stream, Stream, StreamExt};
+
+use crate::{
+    client::Client,
+    orders::status::{order_status, Status},
+};
+
+// Maximum number of status requests in flight during one poll.
+const MAX_CONCURRENT_POLLS: usize = 8;
+
+struct Watch<'a> {
+    client: &'a Client,
+    active: Vec<String>,
+    seen: HashMap<String, (String, Option<String>)>,
+    pending: VecDeque<(String, Status)>,
+    poll_interval: Duration,
+    first_poll: bool,
+}
+
+pub fn watch_orders(
+    client: &Client,
+    ids: Vec<String>,
+    poll_interval: Duration,
+) -> impl Stream<Item = (String, Status)> + '_ {
+    let watch = Watch {
+        client,
+        active: ids,
+        seen: HashMap::new(),
+        pending: VecDeque::new(),
+        poll_interval,
+        first_poll: true,
+    };
+
+    stream::unfold(watch, |mut watch| async move {
+        loop {
+            if let Some(item) = watch.pending.pop_front() {
+                return Some((item, watch));
+            }
+            if watch.active.is_empty() {
+                return None;
+            }
+            if !watch.first_poll {
+                tokio::time::sleep(watch.poll_interval).await;
+            }
+            watch.first_poll = false;
+
+            // Poll all active orders with bounded concurrency.
+            let client = watch.client;
+            let results: Vec<_> = stream::iter(watch.active.clone())
+                .map(|id| async move {
+                    let result = order_status(client, id.clone()).await;
+                    (id, result)
+                })
+                .buffer_unordered(MAX_CONCURRENT_POLLS)
+                .collect()
+                .await;
+
+            for (id, result) in results {
+                let status = match result {
+                    Ok(status) => status,
+                    Err(error) => {
+                        log::warn!("Failed to poll order {}: {}", id, error);
+                        continue;
+                    }
+                };
+
+                // Orders in a terminal status will not change anymore.
+                if status.status_enum().is_terminal() {
+                    watch.active.retain(|active| active != &id);
+                }
+
+                let current = (status.status.clone(), status.validationStatus.clone());
+                if watch.seen.get(&id) != Some(&current) {
+                    watch.seen.insert(id.clone(), current);
+                    watch.pending.push_back((id, status));
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn status(id: &str, status: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": {
+                "id": id,
+                "status": status,
+                "receiveAmount": "1.9",
+                "hashIn": null,
+                "hashOut": null,
+                "validationStatus": null,
+                "createdAt": 1720000000000i64,
+                "updatedAt": 1720000600000i64,
+            }
+        }))
+    }
+
+    async fn mount(server: &MockServer, id: &str, exchanging_polls: u64) {
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .and(query_param("id", id))
+            .respond_with(status(id, "Exchanging"))
+            .up_to_n_times(exchanging_polls)
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/orderStatus"))
+            .and(query_param("id", id))
+            .respond_with(status(id, "Complete"))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_watch_orders() {
+        let server = MockServer::start().await;
+        mount(&server, "first", 1).await;
+        mount(&server, "second", 2).await;
+
+        let client = Client::new(server.uri(), "key".to_string());
+        let updates: Vec<(String, String)> = watch_orders(
+            &client,
+            vec!["first".to_string(), "second".to_string()],
+            Duration::from_millis(10),
+        )
+        .map(|(id, status)| (id, status.status))
+        .collect()
+        .await;
+
+        assert_eq!(updates.len(), 4);
+        assert!(updates[..2].contains(&("first".to_string(), "Exchanging".to_string())));
+        assert!(updates[..2].contains(&("second".to_string(), "Exchanging".to_string())));
+        assert_eq!(updates[2], ("first".to_string(), "Complete".to_string()));
+        assert_eq!(updates[3], ("second".to_string(), "Complete".to_string()));
+    }
+}
@@ -0,0 +1,40 @@
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/**
+   ### Rate limit status reported by the API.
+   Each field is `None` if the response did not include its header.
+   - `limit`: Requests allowed in the current window, from `X-RateLimit-Limit`
+   - `remaining`: Requests left in the current window, from `X-RateLimit-Remaining`
+   - `reset`: When the window resets, from `X-RateLimit-Reset`, as sent by the API
+*/
+pub struct RateLimitStatus {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    pub reset: Option<u64>,
+}
+
+impl RateLimitStatus {
+    /**
+     * Reads the rate limit headers of a response. Returns `None` if none of them are present.
+     */
+    pub fn from_headers(headers: &HeaderMap) -> Option<RateLimitStatus> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+
+        let status = RateLimitStatus {
+            limit: header("x-ratelimit-limit"),
+            remaining: header("x-ratelimit-remaining"),
+            reset: header("x-ratelimit-reset"),
+        };
+        if status.limit.is_none() && status.remaining.is_none() && status.reset.is_none() {
+            return None;
+        }
+        Some(status)
+    }
+}
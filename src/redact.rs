@@ -0,0 +1,99 @@
+#[cfg(feature = "request-logging")]
+use reqwest::header::HeaderMap;
+use serde_json::Value;
+
+// Body fields that identify a user or carry KYC documents.
+const REDACTED_FIELDS: [&str; 8] = [
+    "payload",
+    "userId",
+    "userDeviceId",
+    "receiveAddress",
+    "receiveTag",
+    "refundAddress",
+    "refundTag",
+    "validationData",
+];
+
+// Request headers that carry credentials.
+#[cfg(feature = "request-logging")]
+const REDACTED_HEADERS: [&str; 4] = ["api-key", "authorization", "proxy-authorization", "cookie"];
+
+const REDACTED: &str = "[REDACTED]";
+
+/**
+ * Replaces the values of personal fields in a JSON value with a placeholder, at any depth.
+ * Fields that are null are kept, so that it stays visible whether a field was set.
+ */
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if REDACTED_FIELDS.contains(&key.as_str()) {
+                    if !value.is_null() {
+                        *value = Value::String(REDACTED.to_string());
+                    }
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact),
+        _ => (),
+    }
+}
+
+/**
+ * Pretty-prints a JSON request body with personal fields redacted.
+ * Returns `None` if the body is not JSON, as it could not be redacted.
+ */
+pub fn redacted_body(body: &[u8]) -> Option<String> {
+    let mut value: Value = serde_json::from_slice(body).ok()?;
+    redact(&mut value);
+    serde_json::to_string_pretty(&value).ok()
+}
+
+/**
+ * Formats request headers as `name: value` pairs, with the values of credential headers such as `API-KEY` redacted.
+ */
+#[cfg(feature = "request-logging")]
+pub fn redacted_headers(headers: &HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+                REDACTED
+            } else {
+                value.to_str().unwrap_or("[binary]")
+            };
+            format!("{}: {}", name, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted_body() {
+        let body = serde_json::json!({
+            "id": "order",
+            "amount": 0.1,
+            "receiveAddress": "0xeB2629a2734e272Bcc07BDA959863f316F4bD4Cf",
+            "refundAddress": null,
+            "validationData": { "documents": [{ "uri": "data:image/png;base64,iVBORw0KGgo=" }] },
+        });
+
+        let redacted = redacted_body(body.to_string().as_bytes()).unwrap();
+        let value: Value = serde_json::from_str(&redacted).unwrap();
+        assert_eq!(value["id"], "order");
+        assert_eq!(value["amount"], 0.1);
+        assert_eq!(value["receiveAddress"], REDACTED);
+        assert_eq!(value["refundAddress"], Value::Null);
+        assert_eq!(value["validationData"], REDACTED);
+        assert!(!redacted.contains("0xeB2629a2734e272Bcc07BDA959863f316F4bD4Cf"));
+
+        assert_eq!(redacted_body(b"not json"), None);
+    }
+}
@@ -0,0 +1,275 @@
+use reqwest::{header::HeaderMap, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{EasyBit, Error};
+
+// Keys under which the API may wrap the payload of a response, in order of preference.
+const ENVELOPE_KEYS: [&str; 2] = ["data", "result"];
+
+// Headers under which the API or a proxy in front of it may return a request ID, in order of preference.
+const REQUEST_ID_HEADERS: [&str; 3] = ["x-request-id", "x-correlation-id", "request-id"];
+
+/**
+ * Reads the request ID of a response from the first request ID header that is present.
+ */
+pub fn request_id(headers: &HeaderMap) -> Option<String> {
+    REQUEST_ID_HEADERS
+        .into_iter()
+        .find_map(|name| headers.get(name)?.to_str().ok())
+        .map(str::to_string)
+}
+
+// Adds the HTTP status and request ID of a response to an API error.
+fn with_context(error: Error, status: StatusCode, request_id: Option<String>) -> Error {
+    match error {
+        Error::ApiError(error) => Error::ApiError(EasyBit {
+            status: Some(status),
+            request_id,
+            ..error
+        }),
+        other => other,
+    }
+}
+
+/**
+ * Reads the body of an API response and extracts its payload, see `payload`.
+ * An API error in the body carries the HTTP status and request ID of the response.
+ */
+pub async fn response_payload(response: Response) -> Result<Value, Error> {
+    let status = response.status();
+    let request_id = request_id(response.headers());
+    let json: Value = response.json().await?;
+    payload(json).map_err(|error| with_context(error, status, request_id))
+}
+
+/**
+ * Reads the EasyBit error body of a failed response, with the HTTP status and request ID of the response.
+ */
+pub async fn read_api_error(response: Response) -> Result<EasyBit, Error> {
+    let status = response.status();
+    let request_id = request_id(response.headers());
+    let json: Value = response.json().await?;
+    let error: EasyBit = serde_json::from_value(json)?;
+    Ok(EasyBit {
+        status: Some(status),
+        request_id,
+        ..error
+    })
+}
+
+/**
+ * Turns a failed response into an `Error::ApiError`, see `read_api_error`.
+ * A body that is not an EasyBit error is returned as the error reading it causes.
+ */
+pub async fn api_error(response: Response) -> Error {
+    match read_api_error(response).await {
+        Ok(error) => {
+            log::error!("{:?}", error);
+            Error::ApiError(error)
+        }
+        Err(error) => error,
+    }
+}
+
+/**
+ * Handles the response of an endpoint that returns its data in an envelope.
+ * A successful response is deserialized from its payload, see `payload` and `deserialize`. Any other response is
+ * an `Error::ApiError` with the HTTP status and request ID of the response, see `api_error`.
+ */
+pub async fn handle_data_response<T: DeserializeOwned>(response: Response) -> Result<T, Error> {
+    if response.status() != StatusCode::OK {
+        return Err(api_error(response).await);
+    }
+    let data = response_payload(response).await?;
+    log::debug!("Response data: {:?}", data);
+    deserialize(data)
+}
+
+/**
+ * Extracts the payload from an API response.
+ *
+ * The payload is taken from the first envelope key that is present (`data`, then `result`). A response without
+ * an envelope is an `Error::ApiError` if it carries an `errorCode`, and is otherwise returned as a bare payload.
+ */
+pub fn payload(json: Value) -> Result<Value, Error> {
+    if let Value::Object(map) = &json {
+        for key in ENVELOPE_KEYS {
+            if let Some(data) = map.get(key) {
+                return Ok(data.clone());
+            }
+        }
+        if map.contains_key("errorCode") {
+            let error: EasyBit = serde_json::from_value(json)?;
+            log::error!("{:?}", error);
+            return Err(Error::ApiError(error));
+        }
+    }
+    Ok(json)
+}
+
+/**
+ * Deserializes the `data` payload of an API response.
+ *
+ * With the `path-to-error` feature enabled, failures are reported as `Error::UnexpectedResponse`
+ * including the JSON path of the field that failed, e.g. `data[3].networkList[1].receiveDecimals`.
+ */
+#[cfg(feature = "path-to-error")]
+pub fn deserialize<T: DeserializeOwned>(data: Value) -> Result<T, Error> {
+    serde_path_to_error::deserialize(data).map_err(|error| {
+        let path = match error.path().to_string().as_str() {
+            "." => "data".to_string(),
+            path if path.starts_with('[') => format!("data{}", path),
+            path => format!("data.{}", path),
+        };
+        Error::UnexpectedResponse {
+            path,
+            source: error.into_inner(),
+        }
+    })
+}
+
+/**
+ * Deserializes the `data` payload of an API response.
+ */
+#[cfg(not(feature = "path-to-error"))]
+pub fn deserialize<T: DeserializeOwned>(data: Value) -> Result<T, Error> {
+    Ok(serde_json::from_value(data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::currency::info::Currency;
+    use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_payload_data() {
+        let json = serde_json::json!({ "data": ["BTC_ETH", "ETH_BTC"] });
+        let pairs: Vec<String> = deserialize(payload(json).unwrap()).unwrap();
+        assert_eq!(pairs, vec!["BTC_ETH", "ETH_BTC"]);
+    }
+
+    #[test]
+    fn test_payload_result() {
+        let json = serde_json::json!({ "result": ["BTC_ETH"] });
+        let pairs: Vec<String> = deserialize(payload(json).unwrap()).unwrap();
+        assert_eq!(pairs, vec!["BTC_ETH"]);
+    }
+
+    #[test]
+    fn test_payload_bare() {
+        let json = serde_json::json!(["BTC_ETH"]);
+        let pairs: Vec<String> = deserialize(payload(json).unwrap()).unwrap();
+        assert_eq!(pairs, vec!["BTC_ETH"]);
+    }
+
+    #[test]
+    fn test_request_id() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(request_id(&headers), None);
+
+        headers.insert("x-correlation-id", "correlation".parse().unwrap());
+        assert_eq!(request_id(&headers).as_deref(), Some("correlation"));
+
+        headers.insert("x-request-id", "request".parse().unwrap());
+        assert_eq!(request_id(&headers).as_deref(), Some("request"));
+    }
+
+    #[test]
+    fn test_payload_error() {
+        let json = serde_json::json!({ "success": 0, "errorCode": 401, "errorMessage": "Invalid API key" });
+        match payload(json) {
+            Err(Error::ApiError(error)) => assert_eq!(error.errorCode, 401),
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+    }
+
+    async fn respond(template: ResponseTemplate) -> Response {
+        let server = MockServer::start().await;
+        Mock::given(path("/pairList"))
+            .respond_with(template)
+            .mount(&server)
+            .await;
+        reqwest::get(format!("{}/pairList", server.uri()))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_handle_data_response_success() {
+        let response = respond(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": ["BTC_ETH"] })),
+        )
+        .await;
+
+        let pairs: Vec<String> = handle_data_response(response).await.unwrap();
+        assert_eq!(pairs, vec!["BTC_ETH"]);
+    }
+
+    #[tokio::test]
+    async fn test_handle_data_response_error() {
+        let response = respond(
+            ResponseTemplate::new(401)
+                .insert_header("x-request-id", "req-123")
+                .set_body_json(serde_json::json!({
+                    "success": 0,
+                    "errorCode": 401,
+                    "errorMessage": "Invalid API key"
+                })),
+        )
+        .await;
+
+        match handle_data_response::<Vec<String>>(response).await {
+            Err(Error::ApiError(error)) => {
+                assert_eq!(error.errorCode, 401);
+                assert_eq!(error.status, Some(StatusCode::UNAUTHORIZED));
+                assert_eq!(error.request_id.as_deref(), Some("req-123"));
+            }
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+
+        // An error envelope sent with HTTP 200 is an API error as well.
+        let response = respond(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "success": 0,
+            "errorCode": 429,
+            "errorMessage": "Too many requests"
+        })))
+        .await;
+        match handle_data_response::<Vec<String>>(response).await {
+            Err(Error::ApiError(error)) => {
+                assert_eq!(error.errorCode, 429);
+                assert_eq!(error.status, Some(StatusCode::OK));
+            }
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "path-to-error")]
+    #[test]
+    fn test_deserialize_reports_path() {
+        use crate::test_fixtures::{currency_json, network_json};
+
+        let mut network = network_json("BTC", "Bitcoin");
+        network["receiveDecimals"] = "eight".into();
+        let data = serde_json::json!([currency_json("BTC", "Bitcoin", vec![network])]);
+
+        match deserialize::<Vec<Currency>>(data) {
+            Err(Error::UnexpectedResponse { path, .. }) => {
+                assert_eq!(path, "data[0].networkList[0].receiveDecimals")
+            }
+            other => panic!("Expected an unexpected response error, got {:?}", other),
+        }
+    }
+
+    #[cfg(not(feature = "path-to-error"))]
+    #[test]
+    fn test_deserialize_without_path() {
+        let data = serde_json::json!([{ "currency": "BTC" }]);
+
+        match deserialize::<Vec<Currency>>(data) {
+            Err(Error::DeserializeError(_)) => (),
+            other => panic!("Expected a deserialize error, got {:?}", other),
+        }
+    }
+}
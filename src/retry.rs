@@ -0,0 +1,200 @@
+use std::time::{Duration, Instant};
+
+use reqwest::{header::HeaderMap, Method, StatusCode};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/**
+   ### Retry policy for transient failures.
+   Requests are retried on HTTP 429, 500, 502, 503 and 504, and when connecting or sending times out.
+   - `max_attempts`: Attempts in total, including the first one
+   - `base_delay`: Delay before the first retry, doubled for every following retry
+   - `max_delay`: Longest delay between two attempts. A response asking through `Retry-After` for a longer delay is
+     returned instead of retried, as it must not be retried any earlier.
+   - `retry_posts`: If POST requests such as `create_order` are retried as well. Off by default, as a retried
+     POST may place an order twice.
+*/
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub retry_posts: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            retry_posts: false,
+        }
+    }
+}
+
+impl RetryConfig {
+    /**
+     * Returns true if a request with this method may be retried.
+     */
+    pub fn allows(&self, method: &Method) -> bool {
+        *method == Method::GET || self.retry_posts
+    }
+
+    /**
+     * Returns the delay before the given retry, counting from 1, or `None` if the request must not be retried.
+     * A `Retry-After` delay sent by the API takes precedence over the exponential backoff, which is capped at
+     * `max_delay`. A `Retry-After` delay above `max_delay` returns `None`, as the API must not be asked any earlier.
+     */
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if let Some(retry_after) = retry_after {
+            return (retry_after <= self.max_delay).then_some(retry_after);
+        }
+        let backoff = 2u32
+            .checked_pow(retry.saturating_sub(1))
+            .and_then(|factor| self.base_delay.checked_mul(factor))
+            .unwrap_or(self.max_delay);
+        Some(backoff.min(self.max_delay))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/**
+   ### Budget of retries shared by all requests of a client.
+   Caps retries in aggregate, so that an outage does not multiply the load on the API by the number of attempts.
+   The budget is a token bucket that starts full and refills evenly over the window. Once it is spent, failed
+   requests are returned right away instead of being retried.
+   - `max_retries`: Retries allowed per window, also the most that can be spent in a burst
+   - `window`: Time in which a spent budget refills completely
+*/
+pub struct RetryBudget {
+    pub max_retries: u32,
+    pub window: Duration,
+}
+
+// Token bucket tracking the retries left in a budget.
+#[derive(Debug)]
+pub(crate) struct RetryBucket {
+    budget: RetryBudget,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RetryBucket {
+    pub(crate) fn new(budget: RetryBudget) -> RetryBucket {
+        RetryBucket {
+            budget,
+            tokens: f64::from(budget.max_retries),
+            refilled_at: Instant::now(),
+        }
+    }
+
+    pub(crate) fn budget(&self) -> RetryBudget {
+        self.budget
+    }
+
+    // Takes a token for one retry, returns false if the budget is spent.
+    pub(crate) fn try_acquire(&mut self, now: Instant) -> bool {
+        let capacity = f64::from(self.budget.max_retries);
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.refilled_at = now;
+        self.tokens = if self.budget.window.is_zero() {
+            capacity
+        } else {
+            (self.tokens + capacity * elapsed.as_secs_f64() / self.budget.window.as_secs_f64())
+                .min(capacity)
+        };
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/**
+ * Returns true if a response with this status is worth retrying.
+ */
+pub fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/**
+ * Reads the `Retry-After` header of a response given in seconds. HTTP dates are not supported and are ignored.
+ */
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        let config = RetryConfig {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            retry_posts: false,
+        };
+
+        assert_eq!(config.delay(1, None), Some(Duration::from_millis(100)));
+        assert_eq!(config.delay(3, None), Some(Duration::from_millis(400)));
+        assert_eq!(config.delay(5, None), Some(Duration::from_secs(1)));
+        assert_eq!(config.delay(40, None), Some(Duration::from_secs(1)));
+        assert_eq!(
+            config.delay(1, Some(Duration::from_millis(700))),
+            Some(Duration::from_millis(700))
+        );
+        // Retrying before the time the API asked for is never done.
+        assert_eq!(config.delay(1, Some(Duration::from_secs(60))), None);
+    }
+
+    #[test]
+    fn test_retry_bucket() {
+        let mut bucket = RetryBucket::new(RetryBudget {
+            max_retries: 2,
+            window: Duration::from_secs(10),
+        });
+        let start = bucket.refilled_at;
+
+        assert!(bucket.try_acquire(start));
+        assert!(bucket.try_acquire(start));
+        assert!(!bucket.try_acquire(start));
+
+        // Half the window refills one retry, but never more than the budget.
+        assert!(bucket.try_acquire(start + Duration::from_secs(5)));
+        assert!(!bucket.try_acquire(start + Duration::from_secs(5)));
+        assert!(bucket.try_acquire(start + Duration::from_secs(60)));
+        assert!(bucket.try_acquire(start + Duration::from_secs(60)));
+        assert!(!bucket.try_acquire(start + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(reqwest::header::RETRY_AFTER, "3".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+
+        headers.insert(
+            reqwest::header::RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+}
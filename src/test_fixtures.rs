@@ -0,0 +1,129 @@
+// JSON fixtures of the API types shared by the tests.
+// Tests adjust single fields of a fixture where they need to, so that a schema change is made here only.
+
+use serde_json::Value;
+
+// A network of the currency list, with explorer URLs on example.com.
+pub(crate) fn network_json(network: &str, name: &str) -> Value {
+    serde_json::json!({
+        "network": network,
+        "name": name,
+        "isDefault": true,
+        "sendStatus": true,
+        "receiveStatus": true,
+        "receiveDecimals": 8,
+        "confirmationsMinimum": 2,
+        "confirmationsMaximum": 6,
+        "explorer": "https://example.com",
+        "explorerHash": "https://example.com/tx/",
+        "explorerAddress": "https://example.com/address/",
+        "hasTag": false,
+        "tagName": null,
+        "contractAddress": null,
+        "explorerContract": null
+    })
+}
+
+// USDT on Ethereum, the default network of USDT.
+pub(crate) fn erc20_network_json() -> Value {
+    serde_json::json!({
+        "network": "ETH",
+        "name": "Ethereum (ERC20)",
+        "isDefault": true,
+        "sendStatus": true,
+        "receiveStatus": true,
+        "receiveDecimals": 6,
+        "confirmationsMinimum": 12,
+        "confirmationsMaximum": 64,
+        "explorer": "https://etherscan.io",
+        "explorerHash": "https://etherscan.io/tx/",
+        "explorerAddress": "https://etherscan.io/address/",
+        "hasTag": false,
+        "tagName": null,
+        "contractAddress": "0xdac17f958d2ee523a2206206994597c13d831ec7",
+        "explorerContract": "https://etherscan.io/token/"
+    })
+}
+
+// USDT on Tron.
+pub(crate) fn trc20_network_json() -> Value {
+    serde_json::json!({
+        "network": "TRX",
+        "name": "Tron (TRC20)",
+        "isDefault": false,
+        "sendStatus": true,
+        "receiveStatus": true,
+        "receiveDecimals": 6,
+        "confirmationsMinimum": 20,
+        "confirmationsMaximum": 20,
+        "explorer": "https://tronscan.org",
+        "explorerHash": "https://tronscan.org/#/transaction/",
+        "explorerAddress": "https://tronscan.org/#/address/",
+        "hasTag": false,
+        "tagName": null,
+        "contractAddress": "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t",
+        "explorerContract": "https://tronscan.org/#/token20/"
+    })
+}
+
+// A currency of the currency list that can be sent and received.
+pub(crate) fn currency_json(currency: &str, name: &str, networks: Vec<Value>) -> Value {
+    serde_json::json!({
+        "currency": currency,
+        "name": name,
+        "sendStatusAll": true,
+        "receiveStatusAll": true,
+        "networkList": networks
+    })
+}
+
+// A BTC to ETH order as returned when it is placed.
+pub(crate) fn order_json() -> Value {
+    serde_json::json!({
+        "id": "order",
+        "send": "BTC",
+        "receive": "ETH",
+        "sendNetwork": "BTC",
+        "receiveNetwork": "ETH",
+        "sendAmount": "0.1",
+        "receiveAmount": "1.9",
+        "sendAddress": "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+        "sendTag": null,
+        "receiveAddress": "0xeB2629a2734e272Bcc07BDA959863f316F4bD4Cf",
+        "receiveTag": null,
+        "refundAddress": null,
+        "refundTag": null,
+        "vpm": "off",
+        "createdAt": 1720000000000i64
+    })
+}
+
+// Summary of a BTC to ETH order of the order history.
+pub(crate) fn summary_json(status: &str, send_amount: &str, receive_amount: &str) -> Value {
+    serde_json::json!({
+        "id": "order",
+        "send": "BTC",
+        "receive": "ETH",
+        "sendNetwork": "BTC",
+        "receiveNetwork": "ETH",
+        "sendAmount": send_amount,
+        "receiveAmount": receive_amount,
+        "estimatedSendAmount": "0.1",
+        "estimatedReceiveAmount": "1.9",
+        "sendAddress": "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+        "sendTag": null,
+        "receiveAddress": "0xeB2629a2734e272Bcc07BDA959863f316F4bD4Cf",
+        "receiveTag": null,
+        "refundAddress": null,
+        "refundTag": null,
+        "vpm": "off",
+        "status": status,
+        "hashIn": null,
+        "hashOut": null,
+        "networkFee": "0.001",
+        "earned": "0.0002",
+        "validationStatus": null,
+        "createdAt": 1720000000000i64,
+        "updatedAt": 1720000600000i64
+    })
+}
@@ -0,0 +1,68 @@
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use crate::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+/**
+   ### Result of a call together with its duration.
+   - `value`: Value returned by the call
+   - `elapsed`: Time the call took, including sending the request and reading and parsing the response
+*/
+pub struct Timed<T> {
+    pub value: T,
+    pub elapsed: Duration,
+}
+
+impl<T> Timed<T> {
+    /**
+     * Runs a call and measures how long it takes. Failed calls return their error without a duration.
+     */
+    pub async fn measure(call: impl Future<Output = Result<T, Error>>) -> Result<Timed<T>, Error> {
+        let start = Instant::now();
+        let value = call.await?;
+        Ok(Timed {
+            value,
+            elapsed: start.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_get_account_timed() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/account"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({
+                        "data": {
+                            "level": 1,
+                            "volume": "0",
+                            "fee": "0.004",
+                            "extraFee": "0",
+                            "totalFee": "0.004"
+                        }
+                    }))
+                    .set_delay(Duration::from_millis(20)),
+            )
+            .mount(&server)
+            .await;
+        let client = Client::new(server.uri(), "key".to_string());
+
+        let timed = client.get_account_timed().await.unwrap();
+        assert_eq!(timed.value.level, 1);
+        assert!(timed.elapsed >= Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,87 @@
+// Round trips of every response type through its canonical wire format.
+// A field that is renamed, dropped or retyped in a derive makes the re-serialized JSON differ from the fixture.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{
+    account::Account,
+    currency::{exchange_rate::ExchangeRate, info::Currency, pair_info::Pair},
+    orders::{all::Summary, create::Order, status::Status},
+    test_fixtures::{currency_json, erc20_network_json, order_json, summary_json},
+};
+
+// Deserializes a fixture into `T`, serializes it again and compares the JSON.
+fn assert_round_trip<T: DeserializeOwned + Serialize>(fixture: Value) {
+    let value: T = serde_json::from_value(fixture.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&value).unwrap(), fixture);
+}
+
+#[test]
+fn test_account() {
+    assert_round_trip::<Account>(serde_json::json!({
+        "level": 1,
+        "volume": "12500.75",
+        "fee": "0.004",
+        "extraFee": "0.001",
+        "totalFee": "0.005"
+    }));
+}
+
+#[test]
+fn test_currency() {
+    assert_round_trip::<Currency>(currency_json("USDT", "Tether", vec![erc20_network_json()]));
+}
+
+#[test]
+fn test_exchange_rate() {
+    assert_round_trip::<ExchangeRate>(serde_json::json!({
+        "rate": "19.8",
+        "sendAmount": "1",
+        "receiveAmount": "19.79",
+        "networkFee": "0.01",
+        "confirmations": 2,
+        "processingTime": "5-30 minutes"
+    }));
+}
+
+#[test]
+fn test_pair() {
+    assert_round_trip::<Pair>(serde_json::json!({
+        "minimumAmount": "0.001",
+        "maximumAmount": "10",
+        "networkFee": "0.01",
+        "confirmations": 2,
+        "processingTime": "5-30 minutes"
+    }));
+}
+
+#[test]
+fn test_order() {
+    let mut order = order_json();
+    order["refundAddress"] = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".into();
+    assert_round_trip::<Order>(order);
+}
+
+#[test]
+fn test_status() {
+    assert_round_trip::<Status>(serde_json::json!({
+        "id": "order",
+        "status": "Action Request",
+        "receiveAmount": "1.9",
+        "hashIn": "f4184fc5",
+        "hashOut": null,
+        "validationStatus": "awaiting",
+        "createdAt": 1720000000000i64,
+        "updatedAt": 1720000600000i64
+    }));
+}
+
+#[test]
+fn test_summary() {
+    let mut summary = summary_json("Complete", "0.1", "1.9");
+    summary["estimatedReceiveAmount"] = "1.92".into();
+    summary["hashIn"] = "f4184fc5".into();
+    summary["hashOut"] = "0x5c504ed4".into();
+    assert_round_trip::<Summary>(summary);
+}
@@ -0,0 +1,77 @@
+// Slow request warnings go through the global logger, so this test runs in its own test binary where it can install a
+// logger that records them.
+
+use std::{sync::Mutex, time::Duration};
+
+use easybit::client::Client;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+struct RecordingLogger {
+    warnings: Mutex<Vec<String>>,
+}
+
+impl Log for RecordingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.warnings
+                .lock()
+                .unwrap()
+                .push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: RecordingLogger = RecordingLogger {
+    warnings: Mutex::new(Vec::new()),
+};
+
+#[tokio::test]
+async fn test_slow_request_warning() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Warn);
+
+    let server = MockServer::start().await;
+    Mock::given(path("/pairList"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "data": ["BTC_BTC_ETH_ETH"] }))
+                .set_delay(Duration::from_millis(200)),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(path("/account"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": {
+                "level": 1,
+                "volume": "0",
+                "fee": "0.004",
+                "extraFee": "0",
+                "totalFee": "0.004"
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let client = Client::builder(server.uri(), "key".to_string())
+        .slow_request_threshold(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    client.get_pair_list().await.unwrap();
+    client.get_account().await.unwrap();
+
+    // Only the delayed request is reported, with its path.
+    let warnings = LOGGER.warnings.lock().unwrap();
+    let slow: Vec<&String> = warnings
+        .iter()
+        .filter(|warning| warning.contains("slow request threshold"))
+        .collect();
+    assert_eq!(slow.len(), 1, "{:?}", warnings);
+    assert!(slow[0].starts_with("GET /pairList took "), "{}", slow[0]);
+}